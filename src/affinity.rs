@@ -0,0 +1,133 @@
+use crate::sharder::slots_for;
+use crate::{hash, Slots};
+use std::fmt;
+
+/// Routes the group `group_key` shared by related keys, so they are co-located on one bucket.
+/// It is [`hash`] under a name stating the intent: callers pass the group of a key, e.g. its tenant id,
+/// never the key itself.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, route_with_affinity};
+///
+/// let tenant = 42;
+///
+/// assert_eq!(route_with_affinity(tenant, 1000.into()), hash(tenant, 1000))
+/// ```
+pub fn route_with_affinity(group_key: u64, slots: Slots) -> u32 {
+    hash(group_key, slots)
+}
+
+/// `GroupSharder` routes keys to nodes by their group, extracted from every key by `group_of`,
+/// so all keys of a group always land on the same node. Groups are routed with [`route_with_affinity`].
+///
+/// Example:
+/// ```rust
+/// use jumpch::GroupSharder;
+///
+/// struct Order {
+///     tenant: u64,
+///     id: u64,
+/// }
+///
+/// let sharder = GroupSharder::new(vec!["a", "b", "c"], |order: &Order| order.tenant);
+///
+/// assert_eq!(
+///     sharder.route(&Order { tenant: 42, id: 1 }),
+///     sharder.route(&Order { tenant: 42, id: 2 })
+/// )
+/// ```
+#[derive(Clone)]
+pub struct GroupSharder<T, F> {
+    nodes: Vec<T>,
+    group_of: F,
+}
+
+impl<T, F> GroupSharder<T, F> {
+    /// Create new GroupSharder over `nodes`, grouping keys with `group_of`.
+    ///
+    /// Panics if `nodes` is empty or has more than `u32::MAX` nodes.
+    pub fn new(nodes: Vec<T>, group_of: F) -> Self {
+        slots_for(&nodes);
+        Self { nodes, group_of }
+    }
+
+    /// Returns the nodes in bucket order
+    pub fn nodes(&self) -> &[T] {
+        &self.nodes
+    }
+
+    /// Returns the node index of `key`, which is the node index of its group
+    pub fn index<K: ?Sized>(&self, key: &K) -> usize
+    where
+        F: Fn(&K) -> u64,
+    {
+        route_with_affinity((self.group_of)(key), slots_for(&self.nodes)) as usize
+    }
+
+    /// Returns the node of `key`
+    pub fn route<K: ?Sized>(&self, key: &K) -> &T
+    where
+        F: Fn(&K) -> u64,
+    {
+        &self.nodes[self.index(key)]
+    }
+}
+
+/// Shows the nodes only, the group extractor is opaque and omitted.
+impl<T: fmt::Debug, F> fmt::Debug for GroupSharder<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GroupSharder")
+            .field("nodes", &self.nodes)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, route_with_affinity, GroupSharder, Slots};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_route_with_affinity() {
+        for group in [0, 1, 42, u64::MAX] {
+            assert_eq!(
+                route_with_affinity(group, Slots::from(1000)),
+                hash(group, 1000)
+            );
+        }
+    }
+
+    #[test]
+    fn test_groups_land_together() {
+        let sharder = GroupSharder::new(vec!["a", "b", "c", "d", "e"], |key: &(u64, u64)| key.0);
+        let mut nodes: HashMap<u64, &str> = HashMap::new();
+        for tenant in 0..100 {
+            for id in 0..20 {
+                let node = *sharder.route(&(tenant, id));
+                assert_eq!(*nodes.entry(tenant).or_insert(node), node);
+            }
+        }
+
+        // Different groups still spread over every node.
+        let mut used: Vec<_> = nodes.values().collect();
+        used.sort_unstable();
+        used.dedup();
+        assert_eq!(used.len(), 5);
+    }
+
+    #[test]
+    fn test_debug() {
+        let sharder = GroupSharder::new(vec!["a"], |key: &u64| *key);
+        assert_eq!(
+            format!("{sharder:?}"),
+            r#"GroupSharder { nodes: ["a"], .. }"#
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "sharder must have at least one node")]
+    fn test_empty() {
+        GroupSharder::<&str, _>::new(vec![], |key: &u64| *key);
+    }
+}
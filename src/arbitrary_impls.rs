@@ -0,0 +1,29 @@
+use crate::Slots;
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Generates any slot count, an arbitrary zero is mapped to one slot so the value is always valid.
+impl<'a> Arbitrary<'a> for Slots {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Slots::from(u32::arbitrary(u)?.max(1)))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u32::size_hint(depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Slots;
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn test_arbitrary() {
+        let mut u = Unstructured::new(&[0, 0, 0, 0, 10, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(Slots::arbitrary(&mut u).unwrap(), Slots::from(1));
+        assert_eq!(Slots::arbitrary(&mut u).unwrap(), Slots::from(10));
+        assert_eq!(Slots::arbitrary(&mut u).unwrap(), Slots::from(u32::MAX));
+        // Exhausted input still yields valid slots.
+        assert!(Slots::arbitrary(&mut u).unwrap().get() > 0);
+    }
+}
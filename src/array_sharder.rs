@@ -0,0 +1,65 @@
+use crate::hash;
+
+/// `ArraySharder` routes keys to a fixed array of `N` nodes known at compile time,
+/// with no heap allocation, so it can live on the stack or in a `const`.
+/// Keys are jump hashed directly, like [`NumRouter`](crate::NumRouter).
+///
+/// Fails to compile if `N` is zero or more than `u32::MAX`.
+///
+/// Example:
+/// ```rust
+/// use jumpch::{hash, ArraySharder};
+///
+/// const SHARDER: ArraySharder<&str, 3> = ArraySharder::new(["a", "b", "c"]);
+///
+/// assert_eq!(SHARDER.route(123456), &["a", "b", "c"][hash(123456, 3) as usize])
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArraySharder<T, const N: usize> {
+    nodes: [T; N],
+}
+
+impl<T, const N: usize> ArraySharder<T, N> {
+    const VALID: () = assert!(
+        N > 0 && N <= u32::MAX as usize,
+        "sharder must have between 1 and u32::MAX nodes"
+    );
+
+    /// Create new ArraySharder over `nodes`
+    pub const fn new(nodes: [T; N]) -> Self {
+        let () = Self::VALID;
+        Self { nodes }
+    }
+
+    /// Returns the nodes in bucket order
+    pub const fn nodes(&self) -> &[T; N] {
+        &self.nodes
+    }
+
+    /// Returns the node index of `key`
+    pub fn index(&self, key: u64) -> usize {
+        hash(key, N as u32) as usize
+    }
+
+    /// Returns the node of `key`
+    pub fn route(&self, key: u64) -> &T {
+        &self.nodes[self.index(key)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, ArraySharder};
+
+    #[test]
+    fn test_route() {
+        let nodes = ["a", "b", "c"];
+        let sharder = ArraySharder::new(nodes);
+        for key in 0..1000 {
+            let index = hash(key, 3) as usize;
+            assert_eq!(sharder.index(key), index);
+            assert!(std::ptr::eq(sharder.route(key), &sharder.nodes()[index]));
+            assert_eq!(*sharder.route(key), nodes[index]);
+        }
+    }
+}
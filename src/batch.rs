@@ -0,0 +1,217 @@
+use crate::{hash, hash_str, hash_with_steps, JumpHasher, Slots};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+
+/// Routes every composite key of `pairs`, reusing a single hasher for the whole batch.
+/// Each bucket is equal to the [`hash_pair`](crate::hash_pair) result of the same pair.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash_pair, route_tuples};
+///
+/// let buckets = route_tuples(&[("tenant", 1), ("tenant", 2)], 1000.into());
+///
+/// assert_eq!(buckets, vec![hash_pair(&"tenant", &1, 1000), hash_pair(&"tenant", &2, 1000)])
+/// ```
+pub fn route_tuples<A: Hash, B: Hash>(pairs: &[(A, B)], slots: Slots) -> Vec<u32> {
+    let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(slots.get());
+    pairs
+        .iter()
+        .map(|(a, b)| {
+            a.hash(&mut hasher);
+            b.hash(&mut hasher);
+            hasher.finish_and_reset() as u32
+        })
+        .collect()
+}
+
+/// Routes every string of `keys` into `out` with [`hash_str`], so `out[i]` is the bucket of `keys[i]`.
+/// Empty strings are ordinary keys and always route to the same bucket.
+///
+/// Panics if `out` and `keys` have different lengths.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash_str, hash_strs};
+///
+/// let mut out = [0; 2];
+/// hash_strs(&["a", "b"], 1000.into(), &mut out);
+///
+/// assert_eq!(out, [hash_str("a", 1000), hash_str("b", 1000)])
+/// ```
+pub fn hash_strs(keys: &[&str], slots: Slots, out: &mut [u32]) {
+    assert_eq!(
+        keys.len(),
+        out.len(),
+        "out must have the same length as keys"
+    );
+    for (key, bucket) in keys.iter().zip(out) {
+        *bucket = hash_str(key, slots);
+    }
+}
+
+/// Groups `keys` by bucket for scatter-to-shards dispatch, index `b` of the result holds the keys routed to bucket `b`
+/// in their original order. There is one group per slot, empty groups included.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{group_by_bucket, hash};
+///
+/// let groups = group_by_bucket(&[1, 2, 3], 10.into());
+///
+/// assert_eq!(groups.len(), 10);
+/// assert!(groups[hash(1, 10) as usize].contains(&1))
+/// ```
+pub fn group_by_bucket(keys: &[u64], slots: Slots) -> Vec<Vec<u64>> {
+    let mut groups = vec![Vec::new(); slots.get() as usize];
+    for key in keys {
+        groups[hash(*key, slots) as usize].push(*key);
+    }
+    groups
+}
+
+/// Returns whether `key` is routed to `shard` over `slots`, e.g. as a cheap pre-check before an expensive lookup.
+/// Jump hashing is exact, so unlike a bloom filter there are no false positives.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, is_on_shard};
+///
+/// assert!(is_on_shard(123456, 1000.into(), hash(123456, 1000)))
+/// ```
+pub fn is_on_shard(key: u64, slots: Slots, shard: u32) -> bool {
+    hash(key, slots) == shard
+}
+
+/// Returns the keys of `keys` routed to `shard` over `slots`, in their original order.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, shards_for_keys};
+///
+/// let keys = shards_for_keys(&[1, 2, 3], 10.into(), hash(2, 10));
+///
+/// assert!(keys.contains(&2))
+/// ```
+pub fn shards_for_keys(keys: &[u64], slots: Slots, shard: u32) -> Vec<u64> {
+    keys.iter()
+        .copied()
+        .filter(|key| is_on_shard(*key, slots, shard))
+        .collect()
+}
+
+/// Returns the total number of jumps made while routing every key of `keys`.
+/// Unlike wall-clock timing it is deterministic, so it can be asserted on in CI.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash_with_steps, total_jump_steps};
+///
+/// assert_eq!(total_jump_steps(&[123456], 1000.into()), hash_with_steps(123456, 1000).1 as u64)
+/// ```
+pub fn total_jump_steps(keys: &[u64], slots: Slots) -> u64 {
+    keys.iter()
+        .map(|key| hash_with_steps(*key, slots).1 as u64)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        group_by_bucket, hash, hash_pair, hash_str, hash_strs, is_on_shard, route_tuples,
+        shards_for_keys, total_jump_steps, Slots,
+    };
+
+    #[test]
+    fn test_route_tuples() {
+        let slots = Slots::from(64);
+        let pairs: Vec<_> = (0..100).map(|i| (i % 7, format!("entity {i}"))).collect();
+
+        let buckets = route_tuples(&pairs, slots);
+
+        assert_eq!(buckets.len(), pairs.len());
+        for ((a, b), bucket) in pairs.iter().zip(buckets) {
+            assert_eq!(bucket, hash_pair(a, b, slots));
+        }
+    }
+
+    #[test]
+    fn test_group_by_bucket() {
+        let slots = Slots::from(7);
+        let keys: Vec<u64> = (0..1000).map(|i| i * 31).collect();
+
+        let groups = group_by_bucket(&keys, slots);
+
+        assert_eq!(groups.len(), 7);
+        assert_eq!(groups.iter().map(Vec::len).sum::<usize>(), keys.len());
+        for (bucket, group) in groups.iter().enumerate() {
+            assert!(group.windows(2).all(|pair| pair[0] < pair[1]));
+            for key in group {
+                assert_eq!(hash(*key, slots) as usize, bucket);
+            }
+        }
+        assert!(group_by_bucket(&[], slots).iter().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn test_shards_for_keys() {
+        let slots = Slots::from(7);
+        let keys: Vec<u64> = (0..1000).map(|i| i * 31).collect();
+        let groups = group_by_bucket(&keys, slots);
+
+        for shard in 0..7 {
+            for key in &keys {
+                assert_eq!(is_on_shard(*key, slots, shard), hash(*key, slots) == shard);
+            }
+            assert_eq!(shards_for_keys(&keys, slots, shard), groups[shard as usize]);
+        }
+        assert!(shards_for_keys(&keys, slots, 7).is_empty());
+    }
+
+    #[test]
+    fn test_hash_strs() {
+        let slots = Slots::from(100);
+        let keys = ["tenant", "", "ключ", "", "entity"];
+        let mut out = [u32::MAX; 5];
+
+        hash_strs(&keys, slots, &mut out);
+
+        for (key, bucket) in keys.iter().zip(out) {
+            assert_eq!(bucket, hash_str(key, slots));
+        }
+        assert_eq!(out[1], out[3]);
+        hash_strs(&[], slots, &mut []);
+    }
+
+    #[test]
+    #[should_panic(expected = "out must have the same length as keys")]
+    fn test_hash_strs_length_mismatch() {
+        hash_strs(&["a", "b"], Slots::from(10), &mut [0; 1]);
+    }
+
+    #[test]
+    fn test_total_jump_steps_is_logarithmic() {
+        let keys: Vec<u64> = (0..10_000).map(|i| i * 7919).collect();
+        for slots in [10u32, 100, 1000, 10_000, 100_000] {
+            let mean = total_jump_steps(&keys, Slots::from(slots)) as f64 / keys.len() as f64;
+            // The expected number of jumps is the harmonic number of slots, about ln(slots) + 0.58.
+            let expected = (slots as f64).ln() + 0.58;
+            assert!(
+                (mean - expected).abs() < 0.5,
+                "{slots}: {mean} vs {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_total_jump_steps_frozen() {
+        // A change of these counts means the jump loop does more (or less) work per key,
+        // review it as a performance change.
+        let keys: Vec<u64> = (0..10_000).map(|i| i * 7919).collect();
+        let counts: Vec<u64> = [1, 10, 1000, 65_536, u32::MAX]
+            .into_iter()
+            .map(|slots| total_jump_steps(&keys, Slots::from(slots)))
+            .collect();
+        assert_eq!(counts, vec![10_000, 29_345, 75_304, 117_164, 227_923]);
+    }
+}
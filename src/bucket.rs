@@ -0,0 +1,98 @@
+use core::fmt;
+
+/// `Bucket` is a bucket index returned by jump hashing, a typed alternative to a bare `u32`
+/// for APIs that pass buckets and slot counts side by side.
+/// The formatting and comparison impls only use `core`, so they work without `std`.
+///
+/// Example:
+/// ```rust
+/// use jumpch::{hash, Bucket};
+///
+/// let bucket = Bucket::new(hash(123456, 1000));
+///
+/// assert_eq!(bucket.to_string(), "984");
+/// assert_eq!(u32::from(bucket), 984)
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Bucket(u32);
+
+impl Bucket {
+    /// Create new Bucket with index `bucket`
+    pub const fn new(bucket: u32) -> Self {
+        Self(bucket)
+    }
+
+    /// Returns the bucket index as a primitive integer
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for Bucket {
+    fn from(bucket: u32) -> Self {
+        Self::new(bucket)
+    }
+}
+
+impl From<Bucket> for u32 {
+    fn from(bucket: Bucket) -> Self {
+        bucket.get()
+    }
+}
+
+impl fmt::Display for Bucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Bucket;
+    use core::fmt::Write;
+
+    /// A fixed buffer writer, the way formatting works without an allocator.
+    struct Buffer {
+        bytes: [u8; 32],
+        len: usize,
+    }
+
+    impl Write for Buffer {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            let end = self.len + s.len();
+            self.bytes
+                .get_mut(self.len..end)
+                .ok_or(core::fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_format_into_buffer() {
+        let mut buffer = Buffer {
+            bytes: [0; 32],
+            len: 0,
+        };
+
+        write!(
+            buffer,
+            "{} {:?} {:>5}",
+            Bucket::new(42),
+            Bucket::new(7),
+            Bucket::new(1)
+        )
+        .unwrap();
+
+        assert_eq!(&buffer.bytes[..buffer.len], b"42 Bucket(7)     1");
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(Bucket::new(1) < Bucket::new(2));
+        assert_eq!(Bucket::from(3), Bucket::new(3));
+        assert_eq!(u32::from(Bucket::new(u32::MAX)), u32::MAX);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::JumpHasher;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::marker::PhantomData;
+
+/// `JumpBuildHasherDefault` builds `JumpHasher`s with a compile time slot count, the `JumpHasher`
+/// counterpart of `BuildHasherDefault`, so std collections can be created with `Default`.
+/// Every key hashes to one of `SLOTS` values, so such collections are mostly useful to group keys by bucket.
+///
+/// Fails to compile if `SLOTS` is zero.
+///
+/// Example:
+/// ```rust
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::collections::HashMap;
+/// use jumpch::JumpBuildHasherDefault;
+///
+/// let mut map: HashMap<&str, u32, JumpBuildHasherDefault<DefaultHasher, 64>> = HashMap::default();
+/// map.insert("key", 1);
+///
+/// assert_eq!(map.get("key"), Some(&1))
+/// ```
+pub struct JumpBuildHasherDefault<H, const SLOTS: u32>(PhantomData<fn() -> H>);
+
+impl<H, const SLOTS: u32> JumpBuildHasherDefault<H, SLOTS> {
+    const NON_ZERO: () = assert!(SLOTS > 0, "slots must be greater than 0");
+
+    /// Create new JumpBuildHasherDefault
+    pub const fn new() -> Self {
+        let () = Self::NON_ZERO;
+        Self(PhantomData)
+    }
+}
+
+impl<H: Hasher + Default, const SLOTS: u32> BuildHasher for JumpBuildHasherDefault<H, SLOTS> {
+    type Hasher = JumpHasher<H>;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        JumpHasher::new(SLOTS)
+    }
+}
+
+impl<H, const SLOTS: u32> Default for JumpBuildHasherDefault<H, SLOTS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H, const SLOTS: u32> Clone for JumpBuildHasherDefault<H, SLOTS> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H, const SLOTS: u32> Copy for JumpBuildHasherDefault<H, SLOTS> {}
+
+impl<H, const SLOTS: u32> fmt::Debug for JumpBuildHasherDefault<H, SLOTS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JumpBuildHasherDefault")
+            .field("slots", &SLOTS)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IntoBucket, JumpBuildHasherDefault};
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
+    use std::hash::{BuildHasher, Hash, Hasher};
+
+    #[test]
+    fn test_hash_map() {
+        let mut map: HashMap<u64, u64, JumpBuildHasherDefault<DefaultHasher, 64>> =
+            HashMap::default();
+        for key in 0..1000 {
+            map.insert(key, key * 2);
+        }
+        map.remove(&7);
+
+        assert_eq!(map.len(), 999);
+        assert_eq!(map.get(&42), Some(&84));
+        assert_eq!(map.get(&7), None);
+    }
+
+    #[test]
+    fn test_build_hasher_routes() {
+        let build: JumpBuildHasherDefault<DefaultHasher, 64> = Default::default();
+        for key in 0..100u64 {
+            let mut hasher = build.build_hasher();
+            key.hash(&mut hasher);
+            assert_eq!(hasher.finish(), key.into_bucket(64) as u64);
+        }
+        assert_eq!(format!("{build:?}"), "JumpBuildHasherDefault { slots: 64 }");
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{hash, Slots};
+use lru::LruCache;
+use std::num::NonZeroUsize;
+
+/// `RouteCache` memoizes the buckets of recently routed keys in a small LRU cache,
+/// so hot keys skip the jump hashing loop entirely.
+/// Changing the slot count clears the cache.
+///
+/// Example:
+/// ```rust
+/// use std::num::NonZeroUsize;
+/// use jumpch::{hash, RouteCache};
+///
+/// let mut cache = RouteCache::new(1000.into(), NonZeroUsize::new(128).unwrap());
+///
+/// assert_eq!(cache.route(123456), hash(123456, 1000))
+/// ```
+#[derive(Debug)]
+pub struct RouteCache {
+    slots: Slots,
+    cache: LruCache<u64, u32>,
+}
+
+impl RouteCache {
+    /// Create new RouteCache keeping at most `capacity` recent keys
+    pub fn new(slots: Slots, capacity: NonZeroUsize) -> Self {
+        Self {
+            slots,
+            cache: LruCache::new(capacity),
+        }
+    }
+
+    /// Returns the slot count keys are routed over
+    pub fn slots(&self) -> Slots {
+        self.slots
+    }
+
+    /// Changes the slot count, dropping every cached bucket if it differs from the current one
+    pub fn set_slots(&mut self, slots: Slots) {
+        if self.slots != slots {
+            self.slots = slots;
+            self.cache.clear();
+        }
+    }
+
+    /// Returns the number of cached keys
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Returns `true` if no key is cached
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Returns the bucket of `key`, from the cache if it was routed recently
+    pub fn route(&mut self, key: u64) -> u32 {
+        let slots = self.slots;
+        *self.cache.get_or_insert(key, || hash(key, slots))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, RouteCache, Slots};
+    use std::num::NonZeroUsize;
+
+    fn cache(slots: u32) -> RouteCache {
+        RouteCache::new(Slots::from(slots), NonZeroUsize::new(16).unwrap())
+    }
+
+    #[test]
+    fn test_matches_hash() {
+        let mut cache = cache(100);
+        for key in (0..64).chain(0..64) {
+            assert_eq!(cache.route(key), hash(key, 100));
+        }
+        assert_eq!(cache.len(), 16);
+    }
+
+    #[test]
+    fn test_miss_populates() {
+        let mut cache = cache(100);
+        assert!(cache.is_empty());
+
+        cache.route(42);
+        assert_eq!(cache.len(), 1);
+
+        cache.route(42);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_set_slots_clears() {
+        let mut cache = cache(100);
+        cache.route(123456);
+
+        cache.set_slots(Slots::from(100));
+        assert_eq!(cache.len(), 1);
+
+        cache.set_slots(Slots::from(1000));
+        assert!(cache.is_empty());
+        assert_eq!(cache.route(123456), hash(123456, 1000));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::Sharder;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// `CountingSharder` is a [`Sharder`] counting how many times each node was routed to,
+/// so shard assignment metrics can be exported without instrumenting every call site.
+/// The counters are atomic, so a shared `CountingSharder` can route from many threads.
+///
+/// Example:
+/// ```rust
+/// use jumpch::CountingSharder;
+///
+/// let sharder = CountingSharder::new(vec!["a", "b", "c"]);
+///
+/// for key in 0..100u64 {
+///     sharder.route(&key);
+/// }
+///
+/// assert_eq!(sharder.counts().iter().sum::<u64>(), 100)
+/// ```
+#[derive(Debug)]
+pub struct CountingSharder<T> {
+    sharder: Sharder<T>,
+    counts: Vec<AtomicU64>,
+}
+
+impl<T> CountingSharder<T> {
+    /// Create new CountingSharder over `nodes` with all counters at zero.
+    ///
+    /// Panics if `nodes` is empty or has more than `u32::MAX` nodes.
+    pub fn new(nodes: Vec<T>) -> Self {
+        Self::from(Sharder::new(nodes))
+    }
+
+    /// Returns the wrapped Sharder, routing through it directly isn't counted
+    pub fn sharder(&self) -> &Sharder<T> {
+        &self.sharder
+    }
+
+    /// Returns the node of `key` and counts it
+    pub fn route<K: Hash + ?Sized>(&self, key: &K) -> &T {
+        let index = self.sharder.index(key);
+        self.counts[index].fetch_add(1, Ordering::Relaxed);
+        &self.sharder.nodes()[index]
+    }
+
+    /// Returns how many keys were routed to each node, index `i` counts node `i` of [`Sharder::nodes`]
+    pub fn counts(&self) -> Vec<u64> {
+        self.counts
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    /// Sets every counter back to zero
+    pub fn reset(&self) {
+        for count in &self.counts {
+            count.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<T> From<Sharder<T>> for CountingSharder<T> {
+    fn from(sharder: Sharder<T>) -> Self {
+        let counts = sharder.nodes().iter().map(|_| AtomicU64::new(0)).collect();
+        Self { sharder, counts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CountingSharder, Sharder};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_counts() {
+        let sharder = CountingSharder::new(vec!["a", "b", "c", "d"]);
+        let plain = Sharder::new(vec!["a", "b", "c", "d"]);
+        let mut expected = vec![0u64; 4];
+        for key in 0..1000u64 {
+            assert_eq!(sharder.route(&key), plain.route(&key));
+            expected[plain.index(&key)] += 1;
+        }
+
+        assert_eq!(sharder.counts(), expected);
+
+        sharder.sharder().route(&1u64);
+        assert_eq!(sharder.counts(), expected);
+
+        sharder.reset();
+        assert_eq!(sharder.counts(), vec![0; 4]);
+    }
+
+    #[test]
+    fn test_concurrent_counts() {
+        let sharder = Arc::new(CountingSharder::new(vec!["a", "b", "c"]));
+        let threads: Vec<_> = (0..4u64)
+            .map(|thread| {
+                let sharder = Arc::clone(&sharder);
+                thread::spawn(move || {
+                    for key in thread * 1000..(thread + 1) * 1000 {
+                        sharder.route(&key);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(sharder.counts().iter().sum::<u64>(), 4000);
+    }
+}
@@ -0,0 +1,41 @@
+use std::ptr;
+
+/// Compares two buckets without branching on their value, for contexts where shard assignment is sensitive
+/// and an early-exit comparison could leak it through timing.
+/// It is best effort: it has no data dependent branches, but the compiler gives no formal guarantee.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{ct_eq_bucket, hash};
+///
+/// assert!(ct_eq_bucket(hash(123456, 1000), 984));
+/// assert!(!ct_eq_bucket(1, 2))
+/// ```
+pub fn ct_eq_bucket(a: u32, b: u32) -> bool {
+    let diff = black_box(a ^ b);
+    // The top bit of `diff | -diff` is set if and only if `diff` is not zero.
+    let not_equal = (diff | diff.wrapping_neg()) >> 31;
+    black_box(not_equal) == 0
+}
+
+/// Hides `value` from the optimizer, like `std::hint::black_box` on compilers which have it.
+#[inline(never)]
+fn black_box(value: u32) -> u32 {
+    // SAFETY: `&value` is a valid, aligned reference to an initialized `u32` for the whole read.
+    unsafe { ptr::read_volatile(&value) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ct_eq_bucket;
+
+    #[test]
+    fn test_ct_eq_bucket() {
+        let values = [0, 1, 2, 984, 1 << 31, u32::MAX - 1, u32::MAX];
+        for a in values {
+            for b in values {
+                assert_eq!(ct_eq_bucket(a, b), a == b, "{a} {b}");
+            }
+        }
+    }
+}
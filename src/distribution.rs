@@ -0,0 +1,604 @@
+use crate::{hash, JumpHasher, Slots};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Counts how many of `keys` are routed to each bucket.
+/// Index `b` of the result is the number of keys routed to bucket `b`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::distribution;
+///
+/// let counts = distribution(0..1000, 10.into());
+///
+/// assert_eq!(counts.len(), 10);
+/// assert_eq!(counts.iter().sum::<u64>(), 1000)
+/// ```
+pub fn distribution(keys: impl IntoIterator<Item = u64>, slots: Slots) -> Vec<u64> {
+    let mut counts = vec![0; slots.get() as usize];
+    for key in keys {
+        counts[hash(key, slots) as usize] += 1;
+    }
+    counts
+}
+
+/// Counts how many of `keys` each of the hasher backends `A` and `B` routes to each bucket,
+/// like [`distribution`] for keys hashed through a `JumpHasher<A>` and a `JumpHasher<B>`.
+/// Compare the results, e.g. with [`chi_squared`], to pick a backend for a key set.
+///
+/// Usage example:
+/// ```rust
+/// use std::collections::hash_map::DefaultHasher;
+/// use jumpch::{compare_backends, Fnv1a};
+///
+/// let keys: Vec<String> = (0..1000).map(|key| key.to_string()).collect();
+/// let (default, fnv) = compare_backends::<DefaultHasher, Fnv1a>(&keys, 10.into());
+///
+/// assert_eq!(default.iter().sum::<u64>(), 1000);
+/// assert_eq!(fnv.iter().sum::<u64>(), 1000)
+/// ```
+pub fn compare_backends<A: Hasher + Default, B: Hasher + Default>(
+    keys: &[impl Hash],
+    slots: Slots,
+) -> (Vec<u64>, Vec<u64>) {
+    (
+        backend_distribution::<A, _>(keys, slots),
+        backend_distribution::<B, _>(keys, slots),
+    )
+}
+
+fn backend_distribution<H: Hasher + Default, K: Hash>(keys: &[K], slots: Slots) -> Vec<u64> {
+    let mut counts = vec![0; slots.get() as usize];
+    for key in keys {
+        let mut hasher: JumpHasher<H> = JumpHasher::new(slots.get());
+        key.hash(&mut hasher);
+        counts[hasher.finish() as usize] += 1;
+    }
+    counts
+}
+
+/// Asserts that `keys` are spread over `slots` evenly: every bucket load must be within `tolerance`
+/// of the mean load, as a fraction of it. An empty key set is balanced.
+///
+/// Panics naming the first bucket that is off by more than `tolerance`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::assert_balanced;
+///
+/// assert_balanced!(0..100_000, 10, 0.05);
+/// ```
+///
+/// A skewed key set fails:
+/// ```rust,should_panic
+/// use jumpch::{assert_balanced, hash};
+///
+/// assert_balanced!((0..1000).filter(|key| hash(*key, 4) == 0), 4, 0.5);
+/// ```
+#[macro_export]
+macro_rules! assert_balanced {
+    ($keys:expr, $slots:expr, $tolerance:expr $(,)?) => {{
+        let slots = $crate::Slots::from($slots);
+        let tolerance: f64 = $tolerance;
+        let counts = $crate::distribution($keys, slots);
+        let mean = slots.expected_load(counts.iter().sum());
+        for (bucket, count) in counts.iter().enumerate() {
+            let deviation = if mean > 0.0 {
+                (*count as f64 - mean).abs() / mean
+            } else {
+                0.0
+            };
+            assert!(
+                deviation <= tolerance,
+                "bucket {} has {} keys, {:.1}% off the mean load of {:.1}, tolerance is {:.1}%",
+                bucket,
+                count,
+                deviation * 100.0,
+                mean,
+                tolerance * 100.0
+            );
+        }
+    }};
+}
+
+/// Returns the chi-squared statistic of `counts` against a uniform distribution.
+/// The closer it is to zero, the more evenly the keys are spread.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::chi_squared;
+///
+/// assert_eq!(chi_squared(&[10, 10, 10]), 0.0);
+/// assert_eq!(chi_squared(&[0, 0, 30]), 60.0)
+/// ```
+pub fn chi_squared(counts: &[u64]) -> f64 {
+    let total: u64 = counts.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+
+    let expected = total as f64 / counts.len() as f64;
+    counts
+        .iter()
+        .map(|count| (*count as f64 - expected).powi(2) / expected)
+        .sum()
+}
+
+/// Marks which buckets received at least one of `keys`.
+/// Index `b` of the result is `true` if some key is routed to bucket `b`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, occupied_buckets};
+///
+/// let occupied = occupied_buckets([123456], 1000.into());
+///
+/// assert!(occupied[hash(123456, 1000) as usize]);
+/// assert_eq!(occupied.iter().filter(|o| **o).count(), 1)
+/// ```
+pub fn occupied_buckets(keys: impl IntoIterator<Item = u64>, slots: Slots) -> Vec<bool> {
+    let mut occupied = vec![false; slots.get() as usize];
+    for key in keys {
+        occupied[hash(key, slots) as usize] = true;
+    }
+    occupied
+}
+
+/// `DistributionAccumulator` tracks the bucket distribution of a key stream online, without storing the keys,
+/// in O(slots) memory. It is the streaming counterpart of [`distribution`].
+///
+/// Example:
+/// ```rust
+/// use jumpch::{distribution, DistributionAccumulator};
+///
+/// let mut accumulator = DistributionAccumulator::new(10.into());
+/// for key in 0..1000 {
+///     accumulator.record(key);
+/// }
+///
+/// assert_eq!(accumulator.counts(), distribution(0..1000, 10.into()))
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DistributionAccumulator {
+    slots: Slots,
+    counts: Vec<u64>,
+}
+
+impl DistributionAccumulator {
+    /// Create new DistributionAccumulator over `slots` with every count at zero
+    pub fn new(slots: Slots) -> Self {
+        Self {
+            slots,
+            counts: vec![0; slots.get() as usize],
+        }
+    }
+
+    /// Create new DistributionAccumulator over `slots` with every key of `keys` recorded,
+    /// e.g. at the end of an iterator chain.
+    /// ```rust
+    /// use jumpch::DistributionAccumulator;
+    ///
+    /// let accumulator = DistributionAccumulator::from_keys((0..1000).filter(|key| key % 2 == 0), 10.into());
+    ///
+    /// assert_eq!(accumulator.counts().iter().sum::<u64>(), 500)
+    /// ```
+    pub fn from_keys(keys: impl IntoIterator<Item = u64>, slots: Slots) -> Self {
+        let mut accumulator = Self::new(slots);
+        accumulator.extend(keys);
+        accumulator
+    }
+
+    /// Routes `key` and counts it in its bucket
+    pub fn record(&mut self, key: u64) {
+        self.counts[hash(key, self.slots) as usize] += 1;
+    }
+
+    /// Returns the number of recorded keys routed to each bucket
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Returns the [`chi_squared`] statistic of the recorded keys
+    pub fn chi_squared(&self) -> f64 {
+        chi_squared(&self.counts)
+    }
+}
+
+impl Extend<u64> for DistributionAccumulator {
+    fn extend<I: IntoIterator<Item = u64>>(&mut self, keys: I) {
+        for key in keys {
+            self.record(key);
+        }
+    }
+}
+
+/// `DistributionReport` is the pass/fail verdict of [`verify_distribution`] together with the loads behind it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistributionReport {
+    /// The number of keys routed to each bucket, see [`distribution`]
+    pub counts: Vec<u64>,
+    /// The load of the least loaded bucket
+    pub min: u64,
+    /// The load of the most loaded bucket
+    pub max: u64,
+    /// The mean load of a bucket
+    pub mean: f64,
+    /// The ratio of the most loaded bucket to the mean load, `1.0` for perfectly even loads and no keys
+    pub skew: f64,
+    /// Whether the skew is at most the allowed one
+    pub passed: bool,
+}
+
+/// Verifies that `keys` are spread over `slots` with a [skew](DistributionReport::skew) of at most `max_skew`,
+/// in a single report suited for pre-production gates.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::verify_distribution;
+///
+/// let keys: Vec<u64> = (0..100_000).collect();
+/// let report = verify_distribution(&keys, 10.into(), 1.05);
+///
+/// assert!(report.passed, "{report:?}");
+/// assert!(!verify_distribution(&[42; 1000], 10.into(), 1.05).passed)
+/// ```
+pub fn verify_distribution(keys: &[u64], slots: Slots, max_skew: f64) -> DistributionReport {
+    let counts = distribution(keys.iter().copied(), slots);
+    let min = counts.iter().copied().min().unwrap_or_default();
+    let max = counts.iter().copied().max().unwrap_or_default();
+    let mean = slots.expected_load(keys.len() as u64);
+    let skew = if mean > 0.0 { max as f64 / mean } else { 1.0 };
+    DistributionReport {
+        counts,
+        min,
+        max,
+        mean,
+        skew,
+        passed: skew <= max_skew,
+    }
+}
+
+/// Checks a sample of keys for degenerate input and returns a human-readable caution, or `None` if it looks fine.
+/// Keys that only differ in their low bits, like sequential ids, are mixed well by the algorithm and are fine.
+/// Identical keys always map to the same bucket, so a sample with at least half of its keys repeated is flagged.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::key_entropy_warning;
+///
+/// assert!(key_entropy_warning(&[42; 100]).is_some());
+/// assert!(key_entropy_warning(&(0..100).collect::<Vec<_>>()).is_none())
+/// ```
+pub fn key_entropy_warning(keys: &[u64]) -> Option<String> {
+    if keys.len() < 2 {
+        return None;
+    }
+
+    let mut distinct = keys.to_vec();
+    distinct.sort_unstable();
+    distinct.dedup();
+    match distinct.len() {
+        1 => Some(format!(
+            "all {} keys are identical, they all map to the same bucket",
+            keys.len()
+        )),
+        n if n * 2 <= keys.len() => Some(format!(
+            "only {n} of {} keys are distinct, repeated keys always map to the same bucket",
+            keys.len()
+        )),
+        _ => None,
+    }
+}
+
+/// Share of the keys in one bucket above which [`detect_collapse`] reports the bucket.
+const COLLAPSE_SHARE: f64 = 0.95;
+
+/// Detects the "all keys in one bucket" pathology of a broken key hasher: returns the bucket holding more
+/// than 95% of `keys` over `slots`, or `None` if the keys spread normally.
+/// A single slot holds every key by design and empty samples prove nothing, so both return `None`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::detect_collapse;
+///
+/// assert_eq!(detect_collapse(&[42; 100], 10.into()), Some(jumpch::hash(42, 10)));
+/// assert_eq!(detect_collapse(&(0..100).collect::<Vec<_>>(), 10.into()), None)
+/// ```
+pub fn detect_collapse(keys: &[u64], slots: Slots) -> Option<u32> {
+    if keys.is_empty() || slots.get() == 1 {
+        return None;
+    }
+
+    let counts = distribution(keys.iter().copied(), slots);
+    let (bucket, max) = (0..).zip(counts).max_by_key(|(_, count)| *count)?;
+    (max as f64 > keys.len() as f64 * COLLAPSE_SHARE).then_some(bucket)
+}
+
+/// `DistributionCache` memoizes [`distribution`] reports of one owned key set per slot count,
+/// e.g. for a dashboard refreshing the same report over and over.
+/// The cache never looks at the keys again to detect a change: replace them with [`DistributionCache::set_keys`],
+/// which drops every cached report.
+///
+/// Example:
+/// ```rust
+/// use jumpch::{distribution, DistributionCache};
+///
+/// let keys: Vec<u64> = (0..1000).collect();
+/// let mut cache = DistributionCache::new(keys.clone());
+///
+/// assert_eq!(cache.report(10.into()), distribution(keys, 10.into()))
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DistributionCache {
+    keys: Vec<u64>,
+    reports: HashMap<Slots, Vec<u64>>,
+}
+
+impl DistributionCache {
+    /// Create new DistributionCache of `keys` without any cached report
+    pub fn new(keys: Vec<u64>) -> Self {
+        Self {
+            keys,
+            reports: HashMap::new(),
+        }
+    }
+
+    /// Returns the cached key set
+    pub fn keys(&self) -> &[u64] {
+        &self.keys
+    }
+
+    /// Replaces the cached key set with `keys` and drops every cached report
+    pub fn set_keys(&mut self, keys: Vec<u64>) {
+        self.keys = keys;
+        self.invalidate();
+    }
+
+    /// Drops every cached report, e.g. to free their memory, keeping the key set
+    pub fn invalidate(&mut self) {
+        self.reports.clear();
+    }
+
+    /// Returns the per-bucket counts of the key set over `slots`, computing them only on the first call
+    /// for this slot count since the last [`DistributionCache::set_keys`] or [`DistributionCache::invalidate`]
+    pub fn report(&mut self, slots: Slots) -> &[u64] {
+        let keys = &self.keys;
+        self.reports
+            .entry(slots)
+            .or_insert_with(|| distribution(keys.iter().copied(), slots))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chi_squared, compare_backends, detect_collapse, distribution, hash, key_entropy_warning,
+        occupied_buckets, verify_distribution, DistributionAccumulator, DistributionCache, Fnv1a,
+        Slots,
+    };
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    #[test]
+    fn test_distribution() {
+        let slots = Slots::from(7);
+        let counts = distribution(0..10_000, slots);
+
+        assert_eq!(counts.iter().sum::<u64>(), 10_000);
+        for (bucket, count) in counts.iter().enumerate() {
+            let expected = (0..10_000).filter(|key| hash(*key, slots) as usize == bucket);
+            assert_eq!(*count, expected.count() as u64);
+        }
+    }
+
+    #[test]
+    fn test_distribution_accumulator() {
+        let slots = Slots::from(16);
+        let keys = (0..50_000).map(|key| key * 7919);
+        let mut accumulator = DistributionAccumulator::new(slots);
+        assert_eq!(accumulator.chi_squared(), 0.0);
+
+        for key in keys.clone() {
+            accumulator.record(key);
+        }
+
+        let counts = distribution(keys, slots);
+        assert_eq!(accumulator.counts(), counts);
+        assert_eq!(accumulator.chi_squared(), chi_squared(&counts));
+    }
+
+    #[test]
+    fn test_distribution_accumulator_from_keys() {
+        let slots = Slots::from(8);
+        let keys = (0..10_000u64)
+            .filter(|key| key % 3 != 0)
+            .map(|key| key * 31);
+
+        let mut accumulator = DistributionAccumulator::from_keys(keys.clone(), slots);
+        assert_eq!(accumulator.counts(), distribution(keys.clone(), slots));
+
+        accumulator.extend(keys.clone());
+        let doubled: Vec<u64> = distribution(keys, slots)
+            .iter()
+            .map(|count| count * 2)
+            .collect();
+        assert_eq!(accumulator.counts(), doubled);
+    }
+
+    #[test]
+    fn test_compare_backends() {
+        let keys: Vec<String> = (0..10_000).map(|key| format!("user-{key}")).collect();
+        let slots = Slots::from(8);
+
+        let (default, fnv) = compare_backends::<DefaultHasher, Fnv1a>(&keys, slots);
+
+        for counts in [&default, &fnv] {
+            assert_eq!(counts.len(), 8);
+            assert_eq!(counts.iter().sum::<u64>(), 10_000);
+            assert!(
+                counts.iter().all(|count| count.abs_diff(1250) < 125),
+                "{counts:?}"
+            );
+        }
+        assert_ne!(default, fnv);
+    }
+
+    #[test]
+    fn test_distribution_cache() {
+        let (ten, twenty) = (Slots::from(10), Slots::from(20));
+        let mut cache = DistributionCache::new((0..10_000).collect());
+
+        // A cached report is handed out again from the same allocation instead of being recomputed.
+        let first = cache.report(ten).as_ptr();
+        assert_eq!(cache.report(ten), distribution(0..10_000, ten));
+        assert_eq!(cache.report(ten).as_ptr(), first);
+
+        assert_eq!(cache.report(twenty), distribution(0..10_000, twenty));
+        assert_eq!(cache.report(ten).as_ptr(), first);
+
+        cache.set_keys((0..5000).collect());
+        assert_eq!(cache.keys().len(), 5000);
+        assert_eq!(cache.report(ten), distribution(0..5000, ten));
+        assert_eq!(cache.report(twenty), distribution(0..5000, twenty));
+
+        cache.set_keys(Vec::new());
+        assert_eq!(cache.report(ten), vec![0; 10]);
+        assert_eq!(DistributionCache::default().report(ten), vec![0; 10]);
+    }
+
+    #[test]
+    fn test_distribution_cache_invalidate() {
+        let ten = Slots::from(10);
+        let mut cache = DistributionCache::new((0..10_000).collect());
+        let report = cache.report(ten).to_vec();
+
+        cache.invalidate();
+        assert_eq!(cache.keys().len(), 10_000);
+        assert_eq!(cache.report(ten), report);
+    }
+
+    #[test]
+    fn test_chi_squared() {
+        assert_eq!(chi_squared(&[]), 0.0);
+        assert_eq!(chi_squared(&[0, 0]), 0.0);
+        assert_eq!(chi_squared(&[5, 15]), 5.0);
+    }
+
+    #[test]
+    fn test_occupied_buckets() {
+        let slots = Slots::from(16);
+        let keys = [1, 2, 3, 42, 123456];
+        let occupied = occupied_buckets(keys, slots);
+
+        assert_eq!(occupied.len(), 16);
+        for (bucket, occupied) in occupied.iter().enumerate() {
+            let expected = keys.iter().any(|key| hash(*key, slots) as usize == bucket);
+            assert_eq!(*occupied, expected);
+        }
+    }
+
+    #[test]
+    fn test_occupied_buckets_empty() {
+        assert!(occupied_buckets([], Slots::from(8)).iter().all(|o| !o));
+    }
+
+    #[test]
+    fn test_verify_distribution() {
+        let keys: Vec<u64> = (0..100_000).collect();
+        let slots = Slots::from(10);
+        let report = verify_distribution(&keys, slots, 1.05);
+
+        assert!(report.passed, "{report:?}");
+        assert_eq!(report.counts, distribution(0..100_000, slots));
+        assert_eq!(report.min, *report.counts.iter().min().unwrap());
+        assert_eq!(report.max, *report.counts.iter().max().unwrap());
+        assert_eq!(report.mean, 10_000.0);
+        assert!(report.skew >= 1.0 && report.skew <= 1.05);
+
+        let degenerate = verify_distribution(&[42; 1000], slots, 1.05);
+        assert!(!degenerate.passed);
+        assert_eq!((degenerate.min, degenerate.max), (0, 1000));
+        assert_eq!(degenerate.skew, 10.0);
+
+        let empty = verify_distribution(&[], slots, 1.0);
+        assert!(empty.passed);
+        assert_eq!(empty.skew, 1.0);
+    }
+
+    #[test]
+    fn test_key_entropy_warning() {
+        assert_eq!(
+            key_entropy_warning(&[7; 1000]).as_deref(),
+            Some("all 1000 keys are identical, they all map to the same bucket")
+        );
+        let mostly_repeated: Vec<u64> = (0..1000).map(|key| key % 10).collect();
+        assert_eq!(
+            key_entropy_warning(&mostly_repeated).as_deref(),
+            Some("only 10 of 1000 keys are distinct, repeated keys always map to the same bucket")
+        );
+
+        let sequential: Vec<u64> = (0..1000).collect();
+        assert_eq!(key_entropy_warning(&sequential), None);
+        let spread: Vec<u64> = (0..1000).map(|key| key << 40).collect();
+        assert_eq!(key_entropy_warning(&spread), None);
+        assert_eq!(key_entropy_warning(&[]), None);
+        assert_eq!(key_entropy_warning(&[1]), None);
+    }
+
+    #[test]
+    fn test_detect_collapse() {
+        struct Constant;
+
+        impl Hasher for Constant {
+            fn finish(&self) -> u64 {
+                0xdead_beef
+            }
+
+            fn write(&mut self, _: &[u8]) {}
+        }
+
+        fn hashed<H: Hasher>(new_hasher: impl Fn() -> H) -> Vec<u64> {
+            (0..1000u64)
+                .map(|key| {
+                    let mut hasher = new_hasher();
+                    key.hash(&mut hasher);
+                    hasher.finish()
+                })
+                .collect()
+        }
+        let slots = Slots::from(16);
+
+        let collapsed = hashed(|| Constant);
+        assert_eq!(
+            detect_collapse(&collapsed, slots),
+            Some(hash(0xdead_beef, slots))
+        );
+
+        let spread = hashed(DefaultHasher::new);
+        assert_eq!(detect_collapse(&spread, slots), None);
+
+        let mut mostly_collapsed = vec![7; 960];
+        mostly_collapsed.extend(spread.iter().take(40));
+        assert_eq!(
+            detect_collapse(&mostly_collapsed, slots),
+            Some(hash(7, slots))
+        );
+
+        assert_eq!(detect_collapse(&collapsed, 1.into()), None);
+        assert_eq!(detect_collapse(&[], slots), None);
+    }
+
+    #[test]
+    fn test_assert_balanced() {
+        assert_balanced!(0..100_000, 10, 0.05);
+        assert_balanced!((0..100_000).map(|key| key * 31), Slots::from(16), 0.1);
+        assert_balanced!(std::iter::empty(), 3, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "bucket 0 has 253 keys, 300.0% off the mean load")]
+    fn test_assert_balanced_skewed() {
+        let keys: Vec<u64> = (0..1000).filter(|key| hash(*key, 4) == 0).collect();
+        assert_balanced!(keys, 4, 0.5);
+    }
+}
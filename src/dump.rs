@@ -0,0 +1,148 @@
+use crate::{hash, Slots};
+use std::error::Error;
+use std::fmt;
+
+/// Size of one `(key, bucket)` entry of a routing dump: a little-endian `u64` key followed by a little-endian `u32` bucket.
+const ENTRY_LEN: usize = 12;
+
+/// Snapshots the bucket of every key of `keys` over `slots` into a compact binary blob,
+/// to be checked with [`verify_routing_dump`] after a deploy or a disaster recovery.
+/// The blob is a sequence of 12-byte entries, each a little-endian `u64` key followed by its little-endian `u32` bucket.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::dump_routing;
+///
+/// assert_eq!(dump_routing(&[1, 2, 3], 10.into()).len(), 36)
+/// ```
+pub fn dump_routing(keys: &[u64], slots: Slots) -> Vec<u8> {
+    let mut blob = Vec::with_capacity(keys.len() * ENTRY_LEN);
+    for key in keys {
+        blob.extend_from_slice(&key.to_le_bytes());
+        blob.extend_from_slice(&hash(*key, slots).to_le_bytes());
+    }
+    blob
+}
+
+/// Routes every key of a [`dump_routing`] blob over `slots` again and checks it still lands on the stored bucket,
+/// catching algorithm drift across deploys. Returns the first entry which doesn't match.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{dump_routing, verify_routing_dump};
+///
+/// let blob = dump_routing(&[1, 2, 3], 10.into());
+///
+/// assert_eq!(verify_routing_dump(&blob, 10.into()), Ok(()))
+/// ```
+pub fn verify_routing_dump(blob: &[u8], slots: Slots) -> Result<(), Mismatch> {
+    let entries = blob.chunks_exact(ENTRY_LEN);
+    if !entries.remainder().is_empty() {
+        return Err(Mismatch::Truncated { len: blob.len() });
+    }
+    for entry in entries {
+        let (key, bucket) = entry.split_at(8);
+        let key = u64::from_le_bytes(key.try_into().expect("entry key is 8 bytes"));
+        let stored = u32::from_le_bytes(bucket.try_into().expect("entry bucket is 4 bytes"));
+        let routed = hash(key, slots);
+        if routed != stored {
+            return Err(Mismatch::Bucket {
+                key,
+                stored,
+                routed,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The reason a routing dump doesn't match the current routing, see [`verify_routing_dump`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Mismatch {
+    /// The blob length isn't a whole number of entries.
+    Truncated {
+        /// The blob length in bytes
+        len: usize,
+    },
+    /// A key is routed to another bucket than the stored one.
+    Bucket {
+        /// The key of the entry
+        key: u64,
+        /// The bucket stored in the dump
+        stored: u32,
+        /// The bucket the key is routed to now
+        routed: u32,
+    },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::Truncated { len } => {
+                write!(
+                    f,
+                    "routing dump of {len} bytes isn't a whole number of entries"
+                )
+            }
+            Mismatch::Bucket {
+                key,
+                stored,
+                routed,
+            } => write!(
+                f,
+                "key {key} is routed to bucket {routed}, but the dump stores bucket {stored}"
+            ),
+        }
+    }
+}
+
+impl Error for Mismatch {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{dump_routing, hash, verify_routing_dump, Mismatch, Slots};
+
+    #[test]
+    fn test_routing_dump_round_trip() {
+        let keys: Vec<u64> = (0..1000).map(|key| key * 7919).collect();
+        let slots = Slots::from(100);
+        let blob = dump_routing(&keys, slots);
+
+        assert_eq!(blob.len(), keys.len() * 12);
+        assert_eq!(&blob[12..20], &keys[1].to_le_bytes());
+        assert_eq!(&blob[20..24], &hash(keys[1], slots).to_le_bytes());
+        assert_eq!(verify_routing_dump(&blob, slots), Ok(()));
+        assert_eq!(verify_routing_dump(&[], slots), Ok(()));
+    }
+
+    #[test]
+    fn test_routing_dump_tampered() {
+        let slots = Slots::from(100);
+        let mut blob = dump_routing(&[1, 2, 3], slots);
+        let stored = hash(2, slots);
+        let tampered = (stored + 1) % 100;
+        blob[20..24].copy_from_slice(&tampered.to_le_bytes());
+
+        let mismatch = verify_routing_dump(&blob, slots).unwrap_err();
+        assert_eq!(
+            mismatch,
+            Mismatch::Bucket {
+                key: 2,
+                stored: tampered,
+                routed: stored,
+            }
+        );
+        assert_eq!(
+            mismatch.to_string(),
+            format!("key 2 is routed to bucket {stored}, but the dump stores bucket {tampered}")
+        );
+
+        assert_eq!(
+            verify_routing_dump(&blob[..30], slots),
+            Err(Mismatch::Truncated { len: 30 })
+        );
+        let keys: Vec<u64> = (1..100).collect();
+        assert!(verify_routing_dump(&dump_routing(&keys, slots), 200.into()).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use std::error::Error;
+use std::fmt;
+
+/// The reason a key can't be routed.
+/// New checks may add variants, so matches need a wildcard arm.
+///
+/// Example:
+/// ```rust
+/// use std::collections::hash_map::DefaultHasher;
+/// use jumpch::{JumpHasher, RouteError};
+///
+/// fn route(hasher: &JumpHasher<DefaultHasher>) -> Result<u32, RouteError> {
+///     let bucket = hasher.finish_checked()?;
+///     Ok(bucket)
+/// }
+///
+/// assert_eq!(route(&JumpHasher::new(1000)), Err(RouteError::Unwritten))
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RouteError {
+    /// The hasher was finished before any key was written to it.
+    Unwritten,
+    /// The slot count is zero.
+    ZeroSlots,
+    /// The slot count exceeds the configured sanity cap.
+    SlotsAboveCap {
+        /// The requested slot count
+        slots: u32,
+        /// The largest slot count considered reasonable
+        cap: u32,
+    },
+}
+
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteError::Unwritten => f.write_str("no key was written to the hasher"),
+            RouteError::ZeroSlots => f.write_str("slots must be greater than 0"),
+            RouteError::SlotsAboveCap { slots, cap } => {
+                write!(f, "{slots} slots exceed the cap of {cap} slots")
+            }
+        }
+    }
+}
+
+impl Error for RouteError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::RouteError;
+    use std::error::Error;
+
+    #[test]
+    fn test_error_display() {
+        let error: Box<dyn Error> = Box::new(RouteError::Unwritten);
+        assert_eq!(error.to_string(), "no key was written to the hasher");
+
+        let error = RouteError::SlotsAboveCap {
+            slots: 3_000_000_000,
+            cap: 4096,
+        };
+        assert_eq!(
+            error.to_string(),
+            "3000000000 slots exceed the cap of 4096 slots"
+        );
+        assert_eq!(
+            RouteError::ZeroSlots.to_string(),
+            "slots must be greater than 0"
+        );
+    }
+}
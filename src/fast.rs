@@ -0,0 +1,65 @@
+use crate::{Slots, JUMP_LCG_MULTIPLIER};
+
+/// The largest slot count whose buckets are all exactly representable in `f32`.
+pub const F32_MAX_SLOTS: u32 = 1 << 24;
+
+/// `Jump Consistent Hash` computed with `f32` arithmetic, for targets where `f64` is emulated in software.
+/// It is consistent on its own, but rounding makes a small fraction of keys (under 1% for a few thousand slots)
+/// land on a different bucket than [`hash`](crate::hash), so don't mix both in one deployment.
+///
+/// It is a separate function rather than a replacement of `hash`, because cargo features are unified
+/// across the dependency graph and enabling one must not move the keys of other crates.
+///
+/// Slots must be at most [`F32_MAX_SLOTS`], debug builds assert it.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::hash_f32;
+///
+/// assert!(hash_f32(123456, 1000) < 1000)
+/// ```
+pub fn hash_f32<S: Into<Slots>>(mut key: u64, slots: S) -> u32 {
+    let slots = slots.into().get();
+    debug_assert!(
+        slots <= F32_MAX_SLOTS,
+        "slots must be at most {F32_MAX_SLOTS} for the f32 path"
+    );
+
+    let slots = slots as i64;
+    let (mut b, mut j) = (-1i64, 0i64);
+    while j < slots {
+        b = j;
+        key = key.wrapping_mul(JUMP_LCG_MULTIPLIER).wrapping_add(1);
+        j = ((b + 1) as f32 * ((1u64 << 31) as f32 / ((key >> 33) + 1) as f32)) as i64;
+    }
+    b as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, hash_f32};
+
+    #[test]
+    fn test_matches_f64() {
+        let (mut total, mut matching) = (0u32, 0u32);
+        for slots in (1..=4096).step_by(7) {
+            for key in 0..100 {
+                let bucket = hash_f32(key, slots);
+                assert!(bucket < slots);
+                total += 1;
+                matching += (bucket == hash(key, slots)) as u32;
+            }
+        }
+        assert!(matching as f64 / total as f64 > 0.99, "{matching}/{total}");
+    }
+
+    #[test]
+    fn test_consistent() {
+        for key in 0..1000 {
+            for slots in 1..200 {
+                let (old, new) = (hash_f32(key, slots), hash_f32(key, slots + 1));
+                assert!(old == new || new == slots);
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::hash::Hasher;
+
+const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const PRIME: u64 = 0x100000001b3;
+
+/// 64-bit FNV-1a of `bytes`. Its output is fixed by the specification,
+/// so it is used wherever a routing must not depend on the std hasher.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    fold(OFFSET_BASIS, bytes)
+}
+
+fn fold(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(PRIME))
+}
+
+/// `Fnv1a` is a dependency-free 64-bit FNV-1a `Hasher`, faster than `DefaultHasher` on short keys.
+/// It isn't resistant to crafted keys, so use it for internal sharding only.
+/// Integers are written little-endian, so the result is the same on every platform.
+///
+/// Example:
+/// ```rust
+/// use std::hash::Hasher;
+/// use jumpch::Fnv1a;
+///
+/// let mut hasher = Fnv1a::default();
+/// hasher.write(b"a");
+///
+/// assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c)
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(OFFSET_BASIS)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0 = fold(self.0, bytes)
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fnv1a;
+    use crate::{Fnv1a, JumpHasher};
+    use std::hash::{Hash, Hasher};
+
+    #[test]
+    fn test_reference_vectors() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn test_hasher_matches_fnv1a() {
+        let mut hasher = Fnv1a::default();
+        assert_eq!(hasher.finish(), fnv1a(b""));
+        hasher.write(b"foo");
+        hasher.write(b"bar");
+        assert_eq!(hasher.finish(), fnv1a(b"foobar"));
+
+        let mut hasher = Fnv1a::default();
+        hasher.write_u32(0x01020304);
+        hasher.write_usize(5);
+        assert_eq!(
+            hasher.finish(),
+            fnv1a(&[4, 3, 2, 1, 5, 0, 0, 0, 0, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_with_fnv() {
+        for slots in [1, 10, 1000] {
+            for key in 0..1000u64 {
+                let route = || {
+                    let mut hasher = JumpHasher::with_fnv(slots);
+                    key.hash(&mut hasher);
+                    hasher.finish()
+                };
+                let bucket = route();
+                assert!(bucket < slots as u64);
+                assert_eq!(bucket, route());
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{hash, Slots};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+
+/// `FullRouteCache` memoizes buckets by `(key, slots)`, so unlike `RouteCache` of the `cache` feature
+/// it keeps serving hot keys while the slot count changes back and forth.
+/// It is a plain bounded `HashMap`: once `capacity` entries are cached, the next miss clears it,
+/// which is cheap and works well when a few keys dominate the workload.
+///
+/// Example:
+/// ```rust
+/// use std::num::NonZeroUsize;
+/// use jumpch::{hash, FullRouteCache};
+///
+/// let mut cache = FullRouteCache::new(NonZeroUsize::new(1024).unwrap());
+///
+/// assert_eq!(cache.route(123456, 1000.into()), hash(123456, 1000));
+/// assert_eq!(cache.route(123456, 10.into()), hash(123456, 10))
+/// ```
+#[derive(Clone, Debug)]
+pub struct FullRouteCache {
+    capacity: NonZeroUsize,
+    cache: HashMap<(u64, Slots), u32>,
+}
+
+impl FullRouteCache {
+    /// Create new FullRouteCache keeping at most `capacity` entries
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            capacity,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Returns the maximal number of cached entries
+    pub fn capacity(&self) -> NonZeroUsize {
+        self.capacity
+    }
+
+    /// Returns the number of cached entries
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Returns `true` if nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Returns the bucket of `key` over `slots`, from the cache if this pair was routed before
+    pub fn route(&mut self, key: u64, slots: Slots) -> u32 {
+        if let Some(bucket) = self.cache.get(&(key, slots)) {
+            return *bucket;
+        }
+        if self.cache.len() >= self.capacity.get() {
+            self.cache.clear();
+        }
+        let bucket = hash(key, slots);
+        self.cache.insert((key, slots), bucket);
+        bucket
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, FullRouteCache, Slots};
+    use std::num::NonZeroUsize;
+
+    #[test]
+    fn test_matches_hash_across_slots() {
+        let mut cache = FullRouteCache::new(NonZeroUsize::new(1024).unwrap());
+        for _ in 0..3 {
+            for slots in [10, 11, 1000] {
+                for key in 0..100 {
+                    assert_eq!(cache.route(key, Slots::from(slots)), hash(key, slots));
+                }
+            }
+        }
+        // Every pair is its own entry.
+        assert_eq!(cache.len(), 300);
+    }
+
+    #[test]
+    fn test_bounded() {
+        let mut cache = FullRouteCache::new(NonZeroUsize::new(16).unwrap());
+        assert!(cache.is_empty());
+        for key in 0..100 {
+            assert_eq!(cache.route(key, Slots::from(7)), hash(key, 7));
+            assert!(cache.len() <= cache.capacity().get());
+        }
+
+        cache.route(99, Slots::from(7));
+        let len = cache.len();
+        cache.route(99, Slots::from(7));
+        assert_eq!(cache.len(), len);
+    }
+}
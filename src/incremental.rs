@@ -0,0 +1,141 @@
+use crate::{hash, next_jump, Slots};
+
+/// Returns the bucket of `key` for every slot count in `1..=max` at once,
+/// index `i` of the result holds the bucket for `slots = i + 1`.
+/// A key only moves when it jumps, so a single pass of the algorithm finds all the buckets.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_all_slots};
+///
+/// let buckets = hash_all_slots(123456, 1000);
+///
+/// assert_eq!(buckets[999], hash(123456, 1000))
+/// ```
+pub fn hash_all_slots(mut key: u64, max: u32) -> Vec<u32> {
+    let mut buckets = Vec::with_capacity(max as usize);
+    let mut j = 0i64;
+    while j < max as i64 {
+        let b = j;
+        j = next_jump(&mut key, b);
+        // The key stays in `b` up to `j` slots.
+        let until = j.clamp(b + 1, max as i64) as usize;
+        buckets.resize(until, b as u32);
+    }
+    buckets
+}
+
+/// Returns the buckets of `key` for one slot less, for `slots`, and for one slot more, in a single pass.
+/// The first is `None` when `slots` is one, the last equals the middle one when `slots` is `u32::MAX`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, neighbors};
+///
+/// assert_eq!(
+///     neighbors(123456, 1000.into()),
+///     (Some(hash(123456, 999)), hash(123456, 1000), hash(123456, 1001))
+/// )
+/// ```
+pub fn neighbors(mut key: u64, slots: Slots) -> (Option<u32>, u32, u32) {
+    let n = slots.get() as i64;
+    let targets = [n - 1, n, (n + 1).min(u32::MAX as i64)];
+    let mut buckets = [-1i64; 3];
+
+    let mut j = 0i64;
+    while j < targets[2] {
+        let b = j;
+        for (bucket, target) in buckets.iter_mut().zip(targets) {
+            if b < target {
+                *bucket = b;
+            }
+        }
+        j = next_jump(&mut key, b);
+    }
+
+    let [less, current, more] = buckets;
+    (u32::try_from(less).ok(), current as u32, more as u32)
+}
+
+/// Returns the bucket of `key` together with the slot count at which the key moved to it.
+/// A key only ever jumps to the newest bucket, so it reached bucket `b` when the slot count became `b + 1`
+/// and stays there for every larger count until its next jump. Low buckets are the long-lived assignments.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{bucket_with_stability, hash};
+///
+/// let (bucket, since) = bucket_with_stability(123456, 1000.into());
+///
+/// assert_eq!(bucket, hash(123456, 1000));
+/// assert_eq!(since, bucket + 1)
+/// ```
+pub fn bucket_with_stability(key: u64, slots: Slots) -> (u32, u32) {
+    let bucket = hash(key, slots);
+    // The bucket is less than slots, so adding one can't overflow.
+    (bucket, bucket + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{bucket_with_stability, hash, hash_all_slots, neighbors, Slots};
+
+    #[test]
+    fn test_hash_all_slots() {
+        for key in [0, 1, 42, 123456, u64::MAX] {
+            let buckets = hash_all_slots(key, 2000);
+            assert_eq!(buckets.len(), 2000);
+            for (i, bucket) in buckets.iter().enumerate() {
+                assert_eq!(*bucket, hash(key, i as u32 + 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_all_slots_empty() {
+        assert!(hash_all_slots(123456, 0).is_empty());
+        assert_eq!(hash_all_slots(123456, 1), vec![0]);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        for key in [0, 1, 42, 123456, u64::MAX] {
+            for slots in 2..500 {
+                assert_eq!(
+                    neighbors(key, Slots::from(slots)),
+                    (
+                        Some(hash(key, slots - 1)),
+                        hash(key, slots),
+                        hash(key, slots + 1)
+                    )
+                );
+            }
+            assert_eq!(neighbors(key, Slots::from(1)), (None, 0, hash(key, 2)));
+            let max = Slots::from(u32::MAX);
+            assert_eq!(
+                neighbors(key, max),
+                (
+                    Some(hash(key, u32::MAX - 1)),
+                    hash(key, max),
+                    hash(key, max)
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_bucket_with_stability() {
+        for key in [0, 1, 42, 123456, u64::MAX] {
+            let all = hash_all_slots(key, 1000);
+            for slots in 1..=1000u32 {
+                let (bucket, since) = bucket_with_stability(key, Slots::from(slots));
+                assert_eq!(since, bucket + 1);
+                // The key sits on its bucket for every count from `since` up to `slots`, and not before.
+                assert!(all[since as usize - 1..slots as usize]
+                    .iter()
+                    .all(|b| *b == bucket));
+                assert!(all[..since as usize - 1].iter().all(|b| *b != bucket));
+            }
+        }
+    }
+}
@@ -0,0 +1,639 @@
+use crate::seeded::mix64;
+use crate::{hash, JumpHasher, Slots};
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::CStr;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/// Routes a composite key made of two parts, e.g. a tenant and an entity id.
+/// The parts are hashed in order, so `hash_pair(&a, &b, slots)` and `hash_pair(&b, &a, slots)` may differ.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::hash_pair;
+///
+/// assert_eq!(hash_pair(&"tenant", &42, 1000), hash_pair(&"tenant", &42, 1000))
+/// ```
+pub fn hash_pair<A: Hash, B: Hash, S: Into<Slots>>(a: &A, b: &B, slots: S) -> u32 {
+    let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(slots.into().get());
+    a.hash(&mut hasher);
+    b.hash(&mut hasher);
+    hasher.finish() as u32
+}
+
+/// Routes a 128-bit key, e.g. a UUID, by folding both halves into one `u64` before jumping.
+/// Every bit of the key affects the bucket.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::hash128;
+///
+/// assert!(hash128(u128::MAX, 1000) < 1000)
+/// ```
+pub fn hash128<S: Into<Slots>>(key: u128, slots: S) -> u32 {
+    let (high, low) = ((key >> 64) as u64, key as u64);
+    hash(mix64(high ^ mix64(low)), slots)
+}
+
+/// Routes a 128-bit key given as its `hi` and `lo` 64-bit halves, as returned by hashers with split outputs.
+/// It is the same as [`hash128`] of the recombined key.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash128, hash_halves};
+///
+/// assert_eq!(hash_halves(1, 2, 1000.into()), hash128(1 << 64 | 2, 1000))
+/// ```
+pub fn hash_halves(hi: u64, lo: u64, slots: Slots) -> u32 {
+    hash128((hi as u128) << 64 | lo as u128, slots)
+}
+
+/// Routes a floating-point key by its bits, canonicalized first so equal values route identically:
+/// `-0.0` routes like `0.0` and every NaN routes like `f64::NAN`. The bits are mixed before jumping,
+/// since floats that differ only in a few exponent or mantissa bits are common.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::hash_f64;
+///
+/// assert_eq!(hash_f64(-0.0, 1000.into()), hash_f64(0.0, 1000.into()));
+/// assert_eq!(hash_f64(-f64::NAN, 1000.into()), hash_f64(f64::NAN, 1000.into()))
+/// ```
+pub fn hash_f64(key: f64, slots: Slots) -> u32 {
+    let bits = if key.is_nan() {
+        f64::NAN.to_bits()
+    } else if key == 0.0 {
+        0
+    } else {
+        key.to_bits()
+    };
+    hash(mix64(bits), slots)
+}
+
+/// Routes the little-endian integer encoded in `bytes`, matching producers that serialize keys with `to_le_bytes`.
+/// Shorter inputs are zero-extended, so `[1]` and `[1, 0]` are the same key.
+///
+/// Panics if `bytes` is longer than 8 bytes.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_le};
+///
+/// assert_eq!(hash_le(&123456u64.to_le_bytes(), 1000), hash(123456, 1000));
+/// assert_eq!(hash_le(&123456u32.to_le_bytes(), 1000), hash(123456, 1000))
+/// ```
+pub fn hash_le<S: Into<Slots>>(bytes: &[u8], slots: S) -> u32 {
+    let mut buf = [0; 8];
+    buf[..checked_len(bytes)].copy_from_slice(bytes);
+    hash(u64::from_le_bytes(buf), slots)
+}
+
+/// Routes the big-endian integer encoded in `bytes`, matching producers that serialize keys with `to_be_bytes`.
+/// Shorter inputs are zero-extended, so `[1]` and `[0, 1]` are the same key.
+///
+/// Panics if `bytes` is longer than 8 bytes.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_be};
+///
+/// assert_eq!(hash_be(&123456u64.to_be_bytes(), 1000), hash(123456, 1000));
+/// assert_eq!(hash_be(&123456u32.to_be_bytes(), 1000), hash(123456, 1000))
+/// ```
+pub fn hash_be<S: Into<Slots>>(bytes: &[u8], slots: S) -> u32 {
+    let mut buf = [0; 8];
+    buf[8 - checked_len(bytes)..].copy_from_slice(bytes);
+    hash(u64::from_be_bytes(buf), slots)
+}
+
+fn checked_len(bytes: &[u8]) -> usize {
+    assert!(
+        bytes.len() <= 8,
+        "expected at most 8 bytes, got {}",
+        bytes.len()
+    );
+    bytes.len()
+}
+
+/// Routes raw `bytes` through `DefaultHasher`, without the length marker `<[u8]>::hash` adds.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash_concat, hash_slice};
+///
+/// assert_eq!(hash_slice(b"ab", 1000), hash_concat(&[b"a", b"b"], 1000.into()))
+/// ```
+pub fn hash_slice<S: Into<Slots>>(bytes: &[u8], slots: S) -> u32 {
+    hash_concat(&[bytes], slots.into())
+}
+
+/// Routes the raw UTF-8 bytes of `s`, the string entry point most callers want.
+///
+/// It differs from `s.hash(&mut JumpHasher)`, because `str::hash` also writes a length marker,
+/// so splitting a key into several `str` writes changes its bucket. Here only the bytes count.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash_slice, hash_str};
+///
+/// assert_eq!(hash_str("ab", 1000), hash_slice(b"ab", 1000))
+/// ```
+pub fn hash_str<S: Into<Slots>>(s: &str, slots: S) -> u32 {
+    hash_slice(s.as_bytes(), slots)
+}
+
+/// Routes the bytes of `key` like [`hash_slice`], whether the `Cow` is borrowed or owned,
+/// so parsers handing over `Cow` keys don't need to match on the variant.
+///
+/// Usage example:
+/// ```rust
+/// use std::borrow::Cow;
+/// use jumpch::{hash_cow, hash_slice};
+///
+/// assert_eq!(hash_cow(Cow::Borrowed(b"ab"), 1000), hash_slice(b"ab", 1000));
+/// assert_eq!(hash_cow(Cow::Owned(b"ab".to_vec()), 1000), hash_slice(b"ab", 1000))
+/// ```
+pub fn hash_cow<S: Into<Slots>>(key: Cow<[u8]>, slots: S) -> u32 {
+    hash_slice(&key, slots)
+}
+
+/// Routes the bytes of the C string `s` without its nul terminator, so keys coming through FFI
+/// are routed like the same bytes passed to [`hash_slice`], with no copy.
+///
+/// Usage example:
+/// ```rust
+/// use std::ffi::CStr;
+/// use jumpch::{hash_cstr, hash_slice};
+///
+/// let key = CStr::from_bytes_with_nul(b"ab\0").unwrap();
+///
+/// assert_eq!(hash_cstr(key, 1000), hash_slice(b"ab", 1000))
+/// ```
+pub fn hash_cstr<S: Into<Slots>>(s: &CStr, slots: S) -> u32 {
+    hash_slice(s.to_bytes(), slots)
+}
+
+/// Routes a set of `items`, e.g. tags, as one key. The order of the items and repeated items don't matter,
+/// so `["a", "b"]`, `["b", "a"]` and `["a", "b", "a"]` always route identically.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::hash_set;
+///
+/// assert_eq!(hash_set(&["a", "b"], 1000), hash_set(&["b", "a"], 1000))
+/// ```
+pub fn hash_set<S: Into<Slots>>(items: &[&str], slots: S) -> u32 {
+    let mut hashes: Vec<u64> = items
+        .iter()
+        .map(|item| {
+            let mut hasher = DefaultHasher::new();
+            hasher.write(item.as_bytes());
+            hasher.finish()
+        })
+        .collect();
+    hashes.sort_unstable();
+    hashes.dedup();
+    let key = hashes
+        .iter()
+        .fold(mix64(hashes.len() as u64), |key, item| mix64(key ^ item));
+    hash(key, slots)
+}
+
+/// Routes the concatenation of `parts` as one key.
+///
+/// `str::hash` and `<[u8]>::hash` mix a length marker into the hasher, so hashing `"ab"` and hashing
+/// `"a"` then `"b"` into a `JumpHasher` give different buckets. This function writes the raw bytes only,
+/// so `["ab"]` and `["a", "b"]` always route identically.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::hash_concat;
+///
+/// assert_eq!(hash_concat(&[b"ab"], 1000.into()), hash_concat(&[b"a", b"b"], 1000.into()))
+/// ```
+pub fn hash_concat(parts: &[&[u8]], slots: Slots) -> u32 {
+    let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(slots.get());
+    for part in parts {
+        hasher.write(part);
+    }
+    hasher.finish() as u32
+}
+
+/// Routes the current `finish()` of an externally owned `hasher`, which keeps its state.
+/// Use it when domain data is already fed into a long-lived hasher that can't be wrapped into a `JumpHasher`.
+///
+/// Usage example:
+/// ```rust
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::Hasher;
+/// use jumpch::{hash, hash_with_hasher};
+///
+/// let mut hasher = DefaultHasher::new();
+/// hasher.write(b"domain data");
+///
+/// assert_eq!(hash_with_hasher(&hasher, 1000), hash(hasher.finish(), 1000))
+/// ```
+pub fn hash_with_hasher<H: Hasher, S: Into<Slots>>(hasher: &H, slots: S) -> u32 {
+    hash(hasher.finish(), slots)
+}
+
+/// Routes `key` with a fresh hasher built by `builder`, so any `BuildHasher` of the hashing ecosystem
+/// can be plugged in without wrapping it.
+/// The bucket is stable only as long as the builder is deterministic, a randomly seeded `RandomState`
+/// routes differently in every process.
+///
+/// Usage example:
+/// ```rust
+/// use std::hash::BuildHasherDefault;
+/// use jumpch::{route_with_builder, Fnv1a};
+///
+/// let builder = BuildHasherDefault::<Fnv1a>::default();
+///
+/// assert_eq!(route_with_builder(&"key", 1000, &builder), route_with_builder(&"key", 1000, &builder))
+/// ```
+pub fn route_with_builder<K: Hash + ?Sized, B: BuildHasher, S: Into<Slots>>(
+    key: &K,
+    slots: S,
+    builder: &B,
+) -> u32 {
+    let mut hasher = builder.build_hasher();
+    key.hash(&mut hasher);
+    hash(hasher.finish(), slots)
+}
+
+/// `DynHash` is the object safe form of `Hash`, so keys of different types can be collected into one slice.
+/// It is implemented for every `Hash` type and hashes exactly like `Hash::hash`.
+pub trait DynHash {
+    /// Feeds `self` into `state`
+    fn dyn_hash(&self, state: &mut dyn Hasher);
+}
+
+impl<T: Hash + ?Sized> DynHash for T {
+    fn dyn_hash(&self, mut state: &mut dyn Hasher) {
+        self.hash(&mut state)
+    }
+}
+
+/// Routes a composite key of any arity, hashing every field of `fields` in order through one hasher.
+/// It matches hashing the fields one after another into a `JumpHasher`, so it is order sensitive
+/// and keeps the length markers of `str` and slice fields.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash_fields, hash_pair};
+///
+/// assert_eq!(hash_fields(&[&"tenant", &42], 1000), hash_pair(&"tenant", &42, 1000))
+/// ```
+pub fn hash_fields<S: Into<Slots>>(fields: &[&dyn DynHash], slots: S) -> u32 {
+    let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(slots.into().get());
+    for field in fields {
+        field.dyn_hash(&mut hasher);
+    }
+    hasher.finish() as u32
+}
+
+/// `IntoBucket` is the single entry point for routing a key of any type.
+/// The provided method routes the key's `Hash` through `DefaultHasher`; domain types opt in with an empty impl,
+/// or override it with their own mixing, for example by routing on a numeric id directly.
+/// The std key types, references and boxes of implementors implement it already.
+///
+/// Example:
+/// ```rust
+/// use jumpch::{hash, IntoBucket};
+///
+/// #[derive(Hash)]
+/// struct CustomerId(u64);
+///
+/// impl IntoBucket for CustomerId {
+///     fn into_bucket<S: Into<jumpch::Slots>>(&self, slots: S) -> u32 {
+///         hash(self.0, slots)
+///     }
+/// }
+///
+/// assert_eq!(CustomerId(123456).into_bucket(1000), hash(123456, 1000));
+/// assert!("key".into_bucket(1000) < 1000)
+/// ```
+pub trait IntoBucket: Hash {
+    /// Returns the bucket of `self`
+    #[allow(clippy::wrong_self_convention)]
+    fn into_bucket<S: Into<Slots>>(&self, slots: S) -> u32 {
+        let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(slots.into().get());
+        self.hash(&mut hasher);
+        hasher.finish() as u32
+    }
+}
+
+macro_rules! impl_into_bucket {
+    ($($ty:ty),* $(,)?) => {
+        $(impl IntoBucket for $ty {})*
+    };
+}
+
+impl_into_bucket!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, bool, char, str, String,
+);
+
+impl<T: Hash> IntoBucket for [T] {}
+
+impl<T: Hash, const N: usize> IntoBucket for [T; N] {}
+
+impl<T: Hash> IntoBucket for Vec<T> {}
+
+impl<T: IntoBucket + ?Sized> IntoBucket for &T {
+    fn into_bucket<S: Into<Slots>>(&self, slots: S) -> u32 {
+        (**self).into_bucket(slots)
+    }
+}
+
+impl<T: IntoBucket + ?Sized> IntoBucket for Box<T> {
+    fn into_bucket<S: Into<Slots>>(&self, slots: S) -> u32 {
+        (**self).into_bucket(slots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hash, hash128, hash_be, hash_concat, hash_cow, hash_cstr, hash_f64, hash_fields,
+        hash_halves, hash_le, hash_pair, hash_set, hash_slice, hash_str, hash_with_hasher,
+        route_with_builder, Fnv1a, IntoBucket, JumpHasher, Slots,
+    };
+    use std::borrow::Cow;
+    use std::collections::hash_map::DefaultHasher;
+    use std::ffi::{CStr, CString};
+    use std::hash::{BuildHasherDefault, Hash, Hasher};
+
+    #[test]
+    fn test_hash_pair() {
+        for slots in 1..100 {
+            let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(slots);
+            ("tenant", 42).hash(&mut hasher);
+
+            assert_eq!(hash_pair(&"tenant", &42, slots) as u64, hasher.finish());
+        }
+    }
+
+    #[test]
+    fn test_hash128() {
+        assert_eq!(hash128(0, 1), 0);
+        for slots in [10, 1000] {
+            let buckets: Vec<u32> = (0..64).map(|bit| hash128(1 << (bit + 64), slots)).collect();
+            assert!(buckets.iter().all(|bucket| *bucket < slots));
+            assert!(buckets.iter().any(|bucket| *bucket != buckets[0]));
+        }
+        // Halves aren't interchangeable.
+        let swapped = (1..100u128)
+            .filter(|key| hash128(*key, 1000) != hash128(*key << 64, 1000))
+            .count();
+        assert!(swapped > 90);
+    }
+
+    #[test]
+    fn test_hash_le_be() {
+        for slots in [1, 10, 1000, u32::MAX] {
+            assert_eq!(hash_le(&1u64.to_le_bytes(), slots), hash(1, slots));
+            assert_eq!(hash_be(&1u64.to_be_bytes(), slots), hash(1, slots));
+            assert_eq!(hash_le(&[], slots), hash(0, slots));
+            assert_eq!(hash_be(&[], slots), hash(0, slots));
+
+            let key = 0x0102_0304_0506_0708u64;
+            assert_eq!(hash_le(&key.to_le_bytes(), slots), hash(key, slots));
+            assert_eq!(hash_be(&key.to_be_bytes(), slots), hash(key, slots));
+            assert_eq!(hash_le(&[1, 2], slots), hash(0x0201, slots));
+            assert_eq!(hash_be(&[1, 2], slots), hash(0x0102, slots));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "expected at most 8 bytes, got 9")]
+    fn test_hash_le_too_long() {
+        hash_le(&[0; 9], 10);
+    }
+
+    #[test]
+    fn test_hash_str() {
+        for slots in 1..100 {
+            assert_eq!(hash_str("ab", slots), hash_slice(b"ab", slots));
+            assert_eq!(hash_str("", slots), hash_slice(b"", slots));
+            assert_eq!(
+                hash_str("ключ", slots),
+                hash_slice("ключ".as_bytes(), slots)
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_cow() {
+        let bytes = "ключ".as_bytes();
+        for slots in [1, 10, 1000, u32::MAX] {
+            let borrowed = hash_cow(Cow::Borrowed(bytes), slots);
+            assert_eq!(borrowed, hash_cow(Cow::Owned(bytes.to_vec()), slots));
+            assert_eq!(borrowed, hash_slice(bytes, slots));
+        }
+    }
+
+    #[test]
+    fn test_hash_cstr() {
+        let owned = CString::new("ключ").unwrap();
+        let ab = CStr::from_bytes_with_nul(b"ab\0").unwrap();
+        let empty = CStr::from_bytes_with_nul(b"\0").unwrap();
+        for slots in 1..100 {
+            assert_eq!(hash_cstr(ab, slots), hash_slice(b"ab", slots));
+            assert_eq!(hash_cstr(empty, slots), hash_slice(b"", slots));
+            assert_eq!(
+                hash_cstr(&owned, slots),
+                hash_slice("ключ".as_bytes(), slots)
+            );
+        }
+        assert_ne!(hash_cstr(ab, 1000), hash_slice(b"ab\0", 1000));
+    }
+
+    #[test]
+    fn test_hash_str_differs_from_str_hash() {
+        let differ = (0..100u64)
+            .filter(|key| {
+                let key = key.to_string();
+                key.into_bucket(1000) != hash_str(&key, 1000)
+            })
+            .count();
+        assert!(differ > 90);
+    }
+
+    #[test]
+    fn test_hash_f64() {
+        let nan = f64::from_bits(0x7ff8_dead_beef_0001);
+        assert!(nan.is_nan());
+        for slots in [1, 10, 1000, u32::MAX] {
+            let slots = Slots::from(slots);
+            assert_eq!(hash_f64(-0.0, slots), hash_f64(0.0, slots));
+            assert_eq!(hash_f64(nan, slots), hash_f64(f64::NAN, slots));
+            assert_eq!(hash_f64(-f64::NAN, slots), hash_f64(f64::NAN, slots));
+        }
+
+        let thresholds: Vec<u32> = (0..1000)
+            .map(|i| hash_f64(i as f64 * 0.5, Slots::from(1000)))
+            .collect();
+        let mut distinct = thresholds.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert!(distinct.len() > 500, "{}", distinct.len());
+        assert_ne!(
+            hash_f64(1.0, Slots::from(1000)),
+            hash_f64(-1.0, Slots::from(1000))
+        );
+    }
+
+    #[test]
+    fn test_hash_halves() {
+        for (hi, lo) in [(0, 0), (0, 1), (1, 0), (u64::MAX, 42), (123456, u64::MAX)] {
+            for slots in [1, 10, 1000, u32::MAX] {
+                let key = (hi as u128) << 64 | lo as u128;
+                assert_eq!(hash_halves(hi, lo, Slots::from(slots)), hash128(key, slots));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_set() {
+        for slots in [1, 10, 1000, u32::MAX] {
+            let set = hash_set(&["a", "b", "c"], slots);
+            for permutation in [["a", "c", "b"], ["b", "a", "c"], ["c", "b", "a"]] {
+                assert_eq!(hash_set(&permutation, slots), set);
+            }
+            assert_eq!(hash_set(&["c", "a", "b", "a"], slots), set);
+        }
+
+        let differ = (0..100)
+            .filter(|i| {
+                let (a, b) = (i.to_string(), (i + 1).to_string());
+                hash_set(&[&a, &b], 1000) != hash_set(&[&a], 1000)
+            })
+            .count();
+        assert!(differ > 90);
+        assert!(hash_set(&[], 1000) < 1000);
+    }
+
+    #[test]
+    fn test_hash_concat() {
+        for slots in [1, 10, 1000] {
+            let slots = Slots::from(slots);
+            let whole = hash_concat(&[b"tenant:entity"], slots);
+
+            assert_eq!(hash_concat(&[b"tenant:", b"entity"], slots), whole);
+            assert_eq!(
+                hash_concat(&[b"ten", b"ant:ent", b"", b"ity"], slots),
+                whole
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_with_hasher() {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(b"tenant");
+        hasher.write_u64(42);
+
+        for slots in [1, 10, 1000, u32::MAX] {
+            assert_eq!(
+                hash_with_hasher(&hasher, slots),
+                hash(hasher.finish(), slots)
+            );
+        }
+
+        // The state is kept, feeding more data moves on from it.
+        let before = hasher.finish();
+        hasher.write(b"more");
+        assert_ne!(hasher.finish(), before);
+    }
+
+    #[test]
+    fn test_hash_fields() {
+        for slots in [1, 10, 1000] {
+            let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(slots);
+            ("tenant", 42u64, 'x').hash(&mut hasher);
+            let expected = hasher.finish() as u32;
+
+            assert_eq!(hash_fields(&[&"tenant", &42u64, &'x'], slots), expected);
+            assert_eq!(hash_fields(&[&"tenant", &42u64, &'x'], slots), expected);
+        }
+
+        let reordered = (0..100u64)
+            .filter(|id| {
+                hash_fields(&[&"tenant", id, &'x'], 1000)
+                    != hash_fields(&[id, &"tenant", &'x'], 1000)
+            })
+            .count();
+        assert!(reordered > 90);
+        assert_eq!(
+            hash_fields(&[], 1000),
+            JumpHasher::<DefaultHasher>::new(1000).finish() as u32
+        );
+    }
+
+    #[test]
+    fn test_into_bucket_default() {
+        for slots in 1..100 {
+            let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(slots);
+            "key".hash(&mut hasher);
+
+            assert_eq!("key".into_bucket(slots) as u64, hasher.finish());
+            assert_eq!(
+                String::from("key").into_bucket(slots) as u64,
+                hasher.finish()
+            );
+        }
+
+        #[derive(Hash)]
+        struct OrderId(u64);
+
+        impl IntoBucket for OrderId {}
+
+        for id in 0..100 {
+            assert_eq!(OrderId(id).into_bucket(1000), id.into_bucket(1000));
+        }
+    }
+
+    #[test]
+    fn test_into_bucket_custom() {
+        #[derive(Hash)]
+        struct CustomerId(u64);
+
+        impl IntoBucket for CustomerId {
+            fn into_bucket<S: Into<Slots>>(&self, slots: S) -> u32 {
+                hash(self.0, slots)
+            }
+        }
+
+        let differ = (0..100)
+            .filter(|id| {
+                let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+                CustomerId(*id).hash(&mut hasher);
+                hasher.finish() as u32 != hash(*id, 1000)
+            })
+            .count();
+        assert!(differ > 90);
+
+        for id in 0..100 {
+            let customer = CustomerId(id);
+            assert_eq!(customer.into_bucket(1000), hash(id, 1000));
+            assert_eq!(<&CustomerId>::into_bucket(&&customer, 1000), hash(id, 1000));
+            assert_eq!(Box::new(customer).into_bucket(1000), hash(id, 1000));
+        }
+    }
+
+    #[test]
+    fn test_route_with_builder() {
+        let builder = BuildHasherDefault::<Fnv1a>::default();
+        for key in 0..1000u64 {
+            let mut hasher = JumpHasher::with_fnv(1000);
+            key.hash(&mut hasher);
+            assert_eq!(
+                route_with_builder(&key, 1000, &builder),
+                hasher.finish() as u32
+            );
+        }
+
+        // FNV-1a is unseeded, so the buckets never change between runs.
+        assert_eq!(route_with_builder("key", 1000, &builder), 229);
+    }
+}
@@ -0,0 +1,40 @@
+/// Returns a stable label of `bucket` from `palette`, cycling through it when there are more buckets than labels.
+///
+/// Panics if `palette` is empty.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{bucket_label, hash};
+///
+/// let palette = ["red", "green", "blue"];
+///
+/// assert_eq!(bucket_label(hash(123456, 1000), &palette), "red")
+/// ```
+pub fn bucket_label<'a>(bucket: u32, palette: &[&'a str]) -> &'a str {
+    assert!(!palette.is_empty(), "palette must not be empty");
+    palette[bucket as usize % palette.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bucket_label;
+
+    #[test]
+    fn test_bucket_label() {
+        let palette = ["red", "green", "blue"];
+        let labels: Vec<_> = (0..7)
+            .map(|bucket| bucket_label(bucket, &palette))
+            .collect();
+
+        assert_eq!(
+            labels,
+            ["red", "green", "blue", "red", "green", "blue", "red"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "palette must not be empty")]
+    fn test_empty_palette() {
+        bucket_label(0, &[]);
+    }
+}
@@ -1,5 +1,127 @@
 use std::collections::hash_map::DefaultHasher;
-use std::hash::Hasher;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::num::NonZeroU32;
+
+mod affinity;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+mod array_sharder;
+mod batch;
+mod bucket;
+mod build_hasher;
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "metrics")]
+mod counting;
+mod ct;
+mod distribution;
+mod dump;
+mod error;
+#[cfg(feature = "f32-fast")]
+mod fast;
+mod fnv;
+mod full_cache;
+mod incremental;
+mod keys;
+mod label;
+mod lookup;
+mod migration;
+mod net;
+mod pinned;
+mod precision;
+#[cfg(feature = "proptest")]
+mod proptest_impls;
+#[cfg(test)]
+mod proptests;
+#[cfg(test)]
+mod reference;
+#[cfg(test)]
+mod regressions;
+mod replicas;
+mod router;
+mod scaling;
+mod seeded;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod sharder;
+mod sip;
+mod slots;
+mod sparse;
+pub mod stable_v1;
+#[cfg(feature = "stats")]
+mod stats;
+#[cfg(feature = "tokio")]
+mod stream;
+mod table;
+#[cfg(feature = "uuid")]
+mod uuid_impls;
+mod vnodes;
+mod weighted;
+
+pub use affinity::{route_with_affinity, GroupSharder};
+pub use array_sharder::ArraySharder;
+pub use batch::{
+    group_by_bucket, hash_strs, is_on_shard, route_tuples, shards_for_keys, total_jump_steps,
+};
+pub use bucket::Bucket;
+pub use build_hasher::JumpBuildHasherDefault;
+#[cfg(feature = "cache")]
+pub use cache::RouteCache;
+#[cfg(feature = "metrics")]
+pub use counting::CountingSharder;
+pub use ct::ct_eq_bucket;
+pub use distribution::{
+    chi_squared, compare_backends, detect_collapse, distribution, key_entropy_warning,
+    occupied_buckets, verify_distribution, DistributionAccumulator, DistributionCache,
+    DistributionReport,
+};
+pub use dump::{dump_routing, verify_routing_dump, Mismatch};
+pub use error::RouteError;
+#[cfg(feature = "f32-fast")]
+pub use fast::{hash_f32, F32_MAX_SLOTS};
+pub use fnv::Fnv1a;
+pub use full_cache::FullRouteCache;
+pub use incremental::{bucket_with_stability, hash_all_slots, neighbors};
+pub use keys::{
+    hash128, hash_be, hash_concat, hash_cow, hash_cstr, hash_f64, hash_fields, hash_halves,
+    hash_le, hash_pair, hash_set, hash_slice, hash_str, hash_with_hasher, route_with_builder,
+    DynHash, IntoBucket,
+};
+pub use label::bucket_label;
+pub use lookup::route_into_map;
+pub use migration::{
+    bounded_reshard, dual_route, expected_movement, hash_subrange, is_safe_transition, merge_plan,
+    migration_matrix, on_grow, plan_migration, plan_migration_with_capacity, route_changed,
+    route_percentile, route_warmup, shrink_movers, snapshot_diff, SnapshotDiff,
+};
+pub use net::{hash_ip, hash_socket_ip};
+pub use pinned::PinnedSharder;
+pub use precision::{hash_mode, Precision};
+#[cfg(feature = "proptest")]
+pub use proptest_impls::slots_strategy;
+pub use replicas::{
+    bucket_preference, hash_replicas_into, route_excluding, route_first_available,
+    route_replica_for_writer, weighted_replicas_into,
+};
+pub use router::{ModuloRouter, NumRouter, RendezvousRouter, Router};
+pub use scaling::{growth_schedule, needs_scale_up, rebalance_target, slots_for_target_load};
+pub use seeded::{hash_domain, hash_permuted, hash_seeded, route_windowed};
+pub use sharder::{DuplicateNodeError, Sharder};
+pub use sip::SipHasher13;
+pub use slots::{Slots, SlotsError};
+pub use sparse::SparseSharder;
+#[cfg(feature = "stats")]
+pub use stats::is_uniform;
+#[cfg(feature = "tokio")]
+pub use stream::route_stream;
+#[cfg(feature = "uuid")]
+pub use uuid_impls::hash_uuid;
+pub use vnodes::VirtualSharder;
+pub use weighted::{
+    try_weighted_hash, variant, weighted_hash, weighted_hash_f64, weighted_route,
+    weighted_route_with_share,
+};
 
 /// `JumpHasher` is the wrapper for `Jump Consistent Hash` that implementing `Hasher` trait.
 /// `MUST TO KNOW`: This implementation finish never return value more than `u32`.
@@ -16,10 +138,28 @@ use std::hash::Hasher;
 ///
 /// assert_eq!(hasher.finish(), 677)
 /// ```
-#[derive(Copy, Clone, Debug)]
+///
+/// Generic code works with `&mut JumpHasher` directly: std implements `Hasher` for every `&mut H`,
+/// and it coerces to `&mut dyn Hasher`. A borrowed `&mut dyn Hasher` can be the inner hasher too.
+/// ```rust
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::Hasher;
+/// use jumpch::JumpHasher;
+///
+/// fn feed(hasher: &mut dyn Hasher) {
+///     hasher.write(b"test");
+/// }
+///
+/// let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+/// feed(&mut hasher);
+///
+/// assert!(hasher.finish() < 1000)
+/// ```
+#[derive(Copy, Clone)]
 pub struct JumpHasher<H = DefaultHasher> {
     slots: u32,
     hasher: H,
+    written: bool,
 }
 
 impl<H: Hasher> JumpHasher<H> {
@@ -30,7 +170,77 @@ impl<H: Hasher> JumpHasher<H> {
     ///
     /// let hasher = JumpHasher::new_with_hasher(1000, DefaultHasher::new());
     pub fn new_with_hasher(slots: u32, hasher: H) -> Self {
-        Self { slots, hasher }
+        Self {
+            slots,
+            hasher,
+            written: false,
+        }
+    }
+
+    /// Create new JumpHasher with the hasher built by `build_hasher`
+    /// ```rust
+    /// use std::collections::hash_map::RandomState;
+    /// use jumpch::JumpHasher;
+    ///
+    /// let hasher = JumpHasher::from_build_hasher(1000, &RandomState::new());
+    /// ```
+    pub fn from_build_hasher<B: BuildHasher<Hasher = H>>(slots: u32, build_hasher: &B) -> Self {
+        Self::new_with_hasher(slots, build_hasher.build_hasher())
+    }
+
+    /// Changes the number of slots, keeping the inner hasher and everything written to it
+    /// ```rust
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use jumpch::JumpHasher;
+    ///
+    /// let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+    /// hasher.set_slots(2000);
+    /// ```
+    pub fn set_slots<S: Into<Slots>>(&mut self, slots: S) {
+        self.slots = slots.into().get();
+    }
+
+    /// Returns a one-line summary for operational tooling, `JumpHasher(slots=N, hasher=H, seed=0)`.
+    /// The hasher is the backend type name without its module path, as reported by [`std::any::type_name`],
+    /// which doesn't guarantee the name is the same across compiler versions.
+    /// JumpHasher mixes no seed into keys, so the seed is always reported as zero.
+    /// ```rust
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use jumpch::JumpHasher;
+    ///
+    /// let hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+    ///
+    /// assert_eq!(hasher.describe(), "JumpHasher(slots=1000, hasher=DefaultHasher, seed=0)")
+    /// ```
+    pub fn describe(&self) -> String {
+        let name = std::any::type_name::<H>();
+        let generics = name.find('<').unwrap_or(name.len());
+        let (path, generics) = name.split_at(generics);
+        let hasher = path.rsplit("::").next().unwrap_or(path);
+        format!(
+            "JumpHasher(slots={}, hasher={hasher}{generics}, seed=0)",
+            self.slots
+        )
+    }
+
+    /// Same as `finish`, but returns [`RouteError::Unwritten`] if nothing was written to the hasher,
+    /// which is almost always a forgotten key rather than a real route to a fixed bucket
+    /// ```rust
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hash;
+    /// use jumpch::{JumpHasher, RouteError};
+    ///
+    /// let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+    /// assert_eq!(hasher.finish_checked(), Err(RouteError::Unwritten));
+    ///
+    /// "test".hash(&mut hasher);
+    /// assert_eq!(hasher.finish_checked(), Ok(677));
+    /// ```
+    pub fn finish_checked(&self) -> Result<u32, RouteError> {
+        if !self.written {
+            return Err(RouteError::Unwritten);
+        }
+        Ok(hash_raw(self.hasher.finish(), self.slots))
     }
 }
 
@@ -40,48 +250,471 @@ impl<H: Hasher + Default> JumpHasher<H> {
     /// use std::collections::hash_map::DefaultHasher;
     /// use jumpch::JumpHasher;
     ///
-    /// let hasher = JumpHasher::new(1000);
+    /// let hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
     pub fn new(slots: u32) -> Self {
         Self {
             slots,
             hasher: H::default(),
+            written: false,
         }
     }
+
+    /// Returns the bucket of the written key and resets the inner hasher, so the same
+    /// JumpHasher can route the next key
+    /// ```rust
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::{Hash, Hasher};
+    /// use jumpch::JumpHasher;
+    ///
+    /// let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+    ///
+    /// "test".hash(&mut hasher);
+    /// assert_eq!(hasher.finish_and_reset(), 677);
+    ///
+    /// "test".hash(&mut hasher);
+    /// assert_eq!(hasher.finish_and_reset(), 677);
+    /// ```
+    pub fn finish_and_reset(&mut self) -> u64 {
+        let bucket = self.finish();
+        self.hasher = H::default();
+        self.written = false;
+        bucket
+    }
+
+    /// Returns a JumpHasher with the same slots and a fresh inner hasher, ignoring everything written to `self`
+    /// ```rust
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hasher;
+    /// use jumpch::JumpHasher;
+    ///
+    /// let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+    /// hasher.write(b"previous key");
+    ///
+    /// let fresh = hasher.clone_reset();
+    ///
+    /// assert_eq!(fresh.finish(), JumpHasher::<DefaultHasher>::new(1000).finish())
+    /// ```
+    pub fn clone_reset(&self) -> Self {
+        Self::new(self.slots)
+    }
+}
+
+impl JumpHasher<Fnv1a> {
+    /// Create new JumpHasher with the built-in [`Fnv1a`] hasher, a fast and deterministic choice for internal keys
+    /// ```rust
+    /// use std::hash::{Hash, Hasher};
+    /// use jumpch::JumpHasher;
+    ///
+    /// let mut hasher = JumpHasher::with_fnv(1000);
+    /// "test".hash(&mut hasher);
+    ///
+    /// assert!(hasher.finish() < 1000)
+    /// ```
+    pub fn with_fnv(slots: u32) -> Self {
+        Self::new(slots)
+    }
+}
+
+impl JumpHasher<SipHasher13> {
+    /// Create new JumpHasher over [`SipHasher13`] keyed with the secret `key`.
+    /// Use it when keys come from users, without the secret they can't craft keys that overload one bucket.
+    /// ```rust
+    /// use std::hash::{Hash, Hasher};
+    /// use jumpch::JumpHasher;
+    ///
+    /// let mut hasher = JumpHasher::with_keyed_hash(1000, *b"sixteen byte key");
+    /// "user input".hash(&mut hasher);
+    ///
+    /// assert!(hasher.finish() < 1000)
+    /// ```
+    pub fn with_keyed_hash(slots: u32, key: [u8; 16]) -> Self {
+        Self::new_with_hasher(slots, SipHasher13::new_with_key(key))
+    }
+}
+
+impl<H: Hasher + Clone> JumpHasher<H> {
+    /// Returns a copy of the current hashing state, so a partially written key can be resumed later.
+    /// Both the original and the snapshot continue independently.
+    /// ```rust
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hasher;
+    /// use jumpch::JumpHasher;
+    ///
+    /// let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+    /// hasher.write(b"first half");
+    ///
+    /// let mut resumed = hasher.snapshot();
+    /// hasher.write(b"second half");
+    /// resumed.write(b"second half");
+    ///
+    /// assert_eq!(hasher.finish(), resumed.finish())
+    /// ```
+    pub fn snapshot(&self) -> JumpHasher<H> {
+        self.clone()
+    }
+
+    /// Routes raw `bytes` through a copy of the configured hasher, continuing from everything written so far,
+    /// and leaves `self` untouched, so one configured JumpHasher can route many keys
+    /// ```rust
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hasher;
+    /// use jumpch::JumpHasher;
+    ///
+    /// let hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+    ///
+    /// let mut fresh = hasher.snapshot();
+    /// fresh.write(b"abc");
+    ///
+    /// assert_eq!(hasher.route_bytes(b"abc"), fresh.finish() as u32)
+    /// ```
+    pub fn route_bytes(&self, bytes: &[u8]) -> u32 {
+        let mut hasher = self.hasher.clone();
+        hasher.write(bytes);
+        hash_raw(hasher.finish(), self.slots)
+    }
+}
+
+/// `BoxedJumpHasher` is a JumpHasher with an inner hasher chosen at runtime, e.g. from config.
+/// Every write goes through dynamic dispatch, which costs a little compared to a concrete hasher,
+/// but the type is the same for every backend.
+///
+/// Example:
+/// ```rust
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+/// use jumpch::BoxedJumpHasher;
+///
+/// let mut hasher = BoxedJumpHasher::new_boxed(1000, DefaultHasher::new());
+///
+/// "test".hash(&mut hasher);
+///
+/// assert_eq!(hasher.finish(), 677)
+/// ```
+pub type BoxedJumpHasher = JumpHasher<Box<dyn Hasher>>;
+
+impl BoxedJumpHasher {
+    /// Create new BoxedJumpHasher boxing the `hasher`
+    pub fn new_boxed<H: Hasher + 'static>(slots: u32, hasher: H) -> Self {
+        Self::new_with_hasher(slots, Box::new(hasher))
+    }
+}
+
+/// Shows the slot count only, the state of the inner hasher is opaque and omitted.
+impl<H> fmt::Debug for JumpHasher<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JumpHasher")
+            .field("slots", &self.slots)
+            .finish_non_exhaustive()
+    }
 }
 
 impl<H: Hasher> Hasher for JumpHasher<H> {
     fn finish(&self) -> u64 {
-        hash(self.hasher.finish(), self.slots as i64) as u64
+        hash_raw(self.hasher.finish(), self.slots) as u64
     }
 
     fn write(&mut self, bytes: &[u8]) {
+        self.written = true;
         self.hasher.write(bytes)
     }
+
+    // Integer writes are forwarded as is, so the inner hasher mixes them exactly
+    // like it does without the JumpHasher wrapper.
+
+    fn write_u8(&mut self, i: u8) {
+        self.written = true;
+        self.hasher.write_u8(i)
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.written = true;
+        self.hasher.write_u16(i)
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.written = true;
+        self.hasher.write_u32(i)
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.written = true;
+        self.hasher.write_u64(i)
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.written = true;
+        self.hasher.write_u128(i)
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.written = true;
+        self.hasher.write_usize(i)
+    }
+
+    fn write_i8(&mut self, i: i8) {
+        self.written = true;
+        self.hasher.write_i8(i)
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.written = true;
+        self.hasher.write_i16(i)
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.written = true;
+        self.hasher.write_i32(i)
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.written = true;
+        self.hasher.write_i64(i)
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.written = true;
+        self.hasher.write_i128(i)
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.written = true;
+        self.hasher.write_isize(i)
+    }
 }
 
+/// The multiplier of the linear congruential generator driving the `Jump Consistent Hash` loop.
+/// Re-implementations of the algorithm must use this exact value to stay in sync with [`hash`].
+pub const JUMP_LCG_MULTIPLIER: u64 = 2862933555777941757;
+
 /// The base realization of `Jump Consistent Hash` algorithm.
+/// The result is always less than `slots`, including `slots = u32::MAX`: the jump targets are computed
+/// in `f64` and saturate when converted back, so nothing overflows and the loop always terminates.
+///
+/// Every key is deterministic, `key = 0` included, but zero is a degenerate input of the algorithm:
+/// its first generator step yields `1`, whose jump target is `2^31`, so `hash(0, slots)` is `0` for every
+/// `slots` up to `2^31`. Changing that would move keys, so if raw keys can be zero, mix them first,
+/// e.g. with [`hash_seeded`] or [`IntoBucket`].
 /// Usage example:
 /// ```rust
 /// use jumpch::hash;
 ///
-/// assert_eq!(hash(123456, 1000), 176)
+/// assert_eq!(hash(123456, 1000), 984);
+/// assert_eq!(hash(0, 1000), 0)
 /// ```
-pub fn hash(mut key: u64, slots: i64) -> u32 {
+#[cfg_attr(feature = "inline-always", inline(always))]
+pub fn hash<S: Into<Slots>>(key: u64, slots: S) -> u32 {
+    hash_raw(key, slots.into().get())
+}
+
+/// Same as [`hash`], but returns an error instead of routing when `slots` is zero or exceeds `max_reasonable`,
+/// catching misconfigured slot counts, e.g. billions passed by mistake, before keys are spread over them.
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_bounded, RouteError};
+///
+/// assert_eq!(hash_bounded(123456, 1000, 4096), Ok(hash(123456, 1000)));
+/// assert_eq!(
+///     hash_bounded(123456, 3_000_000_000, 4096),
+///     Err(RouteError::SlotsAboveCap { slots: 3_000_000_000, cap: 4096 })
+/// )
+/// ```
+pub fn hash_bounded(key: u64, slots: u32, max_reasonable: u32) -> Result<u32, RouteError> {
+    if slots > max_reasonable {
+        return Err(RouteError::SlotsAboveCap {
+            slots,
+            cap: max_reasonable,
+        });
+    }
+    let slots = Slots::new(slots).ok_or(RouteError::ZeroSlots)?;
+    Ok(hash(key, slots))
+}
+
+/// Same as [`hash`], but debug builds assert that the bucket is less than `slots`.
+/// Release builds compile it to exactly [`hash`].
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_guarded};
+///
+/// assert_eq!(hash_guarded(123456, 1000.into()), hash(123456, 1000))
+/// ```
+pub fn hash_guarded(key: u64, slots: Slots) -> u32 {
+    let bucket = hash(key, slots);
+    debug_assert!(
+        bucket < slots.get(),
+        "bucket {bucket} is out of range for {} slots",
+        slots.get()
+    );
+    bucket
+}
+
+/// Same as [`hash`] for a primitive slot count, skipping the `Slots` conversion, for profiled inner loops
+/// where the caller already guarantees a valid slot count.
+///
+/// # Safety
+///
+/// `slots` must be at least one. The compiler is told so and may optimize on it,
+/// zero slots is undefined behavior.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_unchecked};
+///
+/// // SAFETY: 1000 is not zero.
+/// assert_eq!(unsafe { hash_unchecked(123456, 1000) }, hash(123456, 1000))
+/// ```
+#[inline]
+pub unsafe fn hash_unchecked(key: u64, slots: u32) -> u32 {
+    if slots == 0 {
+        // SAFETY: the caller guarantees that `slots` is at least one.
+        unsafe { std::hint::unreachable_unchecked() }
+    }
+    hash_raw(key, slots)
+}
+
+/// Returns the bucket of `key` plus one, for tables reserving zero as an empty sentinel.
+/// Subtract one from the result to get the [`hash`] bucket back.
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_nonzero};
+///
+/// assert_eq!(hash_nonzero(123456, 1000).get() - 1, hash(123456, 1000))
+/// ```
+pub fn hash_nonzero<S: Into<Slots>>(key: u64, slots: S) -> NonZeroU32 {
+    // The bucket is less than slots, so adding one can't overflow.
+    NonZeroU32::new(hash(key, slots) + 1).expect("bucket + 1 is never zero")
+}
+
+/// Returns the bucket of `key` together with the number of jumps the algorithm made to find it.
+/// The number of jumps is a deterministic proxy of the CPU cost, it grows logarithmically with `slots`.
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_with_steps};
+///
+/// let (bucket, steps) = hash_with_steps(123456, 1000);
+///
+/// assert_eq!(bucket, hash(123456, 1000));
+/// assert!(steps >= 1)
+/// ```
+pub fn hash_with_steps<S: Into<Slots>>(key: u64, slots: S) -> (u32, u32) {
+    jump(key, slots.into().get())
+}
+
+/// Same as [`hash`], but usable in const contexts, e.g. to precompute tables with [`jump_table!`].
+/// Float arithmetic isn't available in const functions on the supported compilers, so the jump targets
+/// are computed with integers that reproduce the `f64` rounding of [`hash`] bit for bit.
+///
+/// Panics if `slots` is zero, at compile time when evaluated in a const.
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_const};
+///
+/// const BUCKET: u32 = hash_const(123456, 1000);
+///
+/// assert_eq!(BUCKET, hash(123456, 1000))
+/// ```
+pub const fn hash_const(mut key: u64, slots: u32) -> u32 {
+    assert!(slots > 0, "slots must be greater than 0");
+    let slots = slots as i64;
     let (mut b, mut j) = (-1i64, 0i64);
     while j < slots {
         b = j;
-        key = key.wrapping_mul(2862933555777941757).wrapping_add(1);
-        j = ((b.wrapping_add(1) as f64) * (((1u64 << 31) as f64) / (((key >> 33) + 1) as f64)))
-            as i64;
+        key = key.wrapping_mul(JUMP_LCG_MULTIPLIER).wrapping_add(1);
+        j = const_jump_target(b, key >> 33);
     }
     b as u32
 }
 
+/// Computes `((b + 1) as f64 * ((1u64 << 31) as f64 / (r + 1) as f64)) as i64` without floats.
+/// Both operands of the division and of the multiplication are exact in `f64`, so the result only depends on
+/// the round-to-nearest-even of the quotient and of the product to 53 significant bits, which is done here on `u128`.
+const fn const_jump_target(b: i64, r: u64) -> i64 {
+    let divisor = (r + 1) as u128;
+    // Scaling by 2^shift puts the quotient into [2^52, 2^53], so the integer part holds exactly the 53 kept bits.
+    let shift = 21 + (128 - divisor.leading_zeros());
+    let quotient = round_half_even(
+        (1u128 << (31 + shift)) / divisor,
+        (1u128 << (31 + shift)) % divisor,
+        divisor,
+    );
+
+    let mut product = (b + 1) as u128 * quotient;
+    let bits = 128 - product.leading_zeros();
+    if bits > 53 {
+        let dropped = bits - 53;
+        let kept = product >> dropped;
+        let rest = product & ((1 << dropped) - 1);
+        product = round_half_even(kept, rest, 1 << dropped) << dropped;
+    }
+
+    let target = product >> shift;
+    if target > i64::MAX as u128 {
+        i64::MAX
+    } else {
+        target as i64
+    }
+}
+
+/// Rounds `value + rest / unit` to the nearest integer, ties to even.
+const fn round_half_even(value: u128, rest: u128, unit: u128) -> u128 {
+    if rest * 2 > unit || (rest * 2 == unit && value & 1 == 1) {
+        value + 1
+    } else {
+        value
+    }
+}
+
+/// The algorithm loop without the `Slots` conversion.
+/// Zero slots yields `u32::MAX`, which only `JumpHasher` relies on.
+#[cfg_attr(feature = "inline-always", inline(always))]
+fn hash_raw(key: u64, slots: u32) -> u32 {
+    jump(key, slots).0
+}
+
+#[inline]
+fn jump(mut key: u64, slots: u32) -> (u32, u32) {
+    let slots = slots as i64;
+    let (mut b, mut j, mut steps) = (-1i64, 0i64, 0u32);
+    while j < slots {
+        b = j;
+        j = next_jump(&mut key, b);
+        steps += 1;
+    }
+    (b as u32, steps)
+}
+
+/// Advances the generator and returns the next bucket the key jumps to from bucket `b`.
+#[inline]
+pub(crate) fn next_jump(key: &mut u64, b: i64) -> i64 {
+    *key = key.wrapping_mul(JUMP_LCG_MULTIPLIER).wrapping_add(1);
+    ((b.wrapping_add(1) as f64) * (((1u64 << 31) as f64) / (((*key >> 33) + 1) as f64))) as i64
+}
+
+/// Asserts the invariants every bucket must satisfy, shared by the property tests and the regression cases.
+#[cfg(test)]
+pub(crate) fn check(key: u64, slots: u32) {
+    let slots = Slots::from(slots);
+    let bucket = hash(key, slots);
+    assert!(bucket < slots.get(), "{bucket} >= {}", slots.get());
+    assert_eq!(bucket, hash(key, slots), "hash is not deterministic");
+
+    if let Some(grown) = slots.get().checked_add(1) {
+        let moved = hash(key, grown);
+        assert!(
+            moved == bucket || moved == slots.get(),
+            "growing to {grown} slots moved the key from {bucket} to {moved}"
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::JumpHasher;
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
+    use crate::{
+        const_jump_target, hash, hash_bounded, hash_const, hash_guarded, hash_nonzero,
+        hash_unchecked, hash_with_steps, next_jump, BoxedJumpHasher, JumpHasher, RouteError, Slots,
+        JUMP_LCG_MULTIPLIER,
+    };
+    use std::collections::hash_map::{DefaultHasher, RandomState};
+    use std::hash::{BuildHasherDefault, Hash, Hasher};
 
     #[test]
     fn test_struct() {
@@ -104,6 +737,395 @@ mod tests {
         check_range(&test);
     }
 
+    #[cfg(feature = "inline-always")]
+    #[test]
+    fn test_inline_always() {
+        assert_eq!(hash(123456, 1000), 984);
+        assert_eq!(hash(42, 1 << 16), 5747);
+        for key in 0..1000 {
+            for slots in [1, 10, 1000, u32::MAX] {
+                assert_eq!(hash(key, slots), hash_const(key, slots));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_const() {
+        const BUCKET: u32 = hash_const(42, 1 << 16);
+        assert_eq!(BUCKET, 5747);
+        for key in 0..1000 {
+            for slots in [1, 10, 1000, u32::MAX] {
+                assert_eq!(hash_const(key, slots), hash(key, slots));
+            }
+        }
+
+        let mut state = 1u64;
+        for _ in 0..100_000 {
+            state = crate::seeded::mix64(state);
+            let slots = (state >> 32) as u32 >> (state % 32);
+            assert_eq!(hash_const(state, slots.max(1)), hash(state, slots.max(1)));
+        }
+    }
+
+    #[test]
+    fn test_const_jump_target() {
+        let f64_target =
+            |b: i64, r: u64| ((b + 1) as f64 * ((1u64 << 31) as f64 / (r + 1) as f64)) as i64;
+        let edges = [
+            0,
+            1,
+            2,
+            3,
+            (1 << 31) - 1,
+            1 << 31,
+            (1 << 32) - 2,
+            (1 << 32) - 1,
+        ];
+        for b in edges {
+            for r in [0, 1, 2, 3, 1 << 30, (1 << 31) - 2, (1 << 31) - 1] {
+                assert_eq!(const_jump_target(b, r), f64_target(b, r), "{b} {r}");
+            }
+        }
+
+        let mut state = 7u64;
+        for _ in 0..1_000_000 {
+            state = crate::seeded::mix64(state);
+            let (b, r) = (
+                (state >> 32) as i64 >> (state % 32),
+                (state & 0x7fff_ffff) >> (state % 31),
+            );
+            assert_eq!(const_jump_target(b, r), f64_target(b, r), "{b} {r}");
+        }
+    }
+
+    #[test]
+    fn test_dyn_hasher() {
+        fn feed(hasher: &mut dyn Hasher, key: &str) {
+            key.hash(&mut &mut *hasher);
+        }
+        fn feed_generic<H: Hasher>(mut hasher: H, key: &str) -> u64 {
+            key.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut expected: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        "key".hash(&mut expected);
+
+        let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        feed(&mut hasher, "key");
+        assert_eq!(hasher.finish(), expected.finish());
+
+        let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        assert_eq!(feed_generic(&mut hasher, "key"), expected.finish());
+
+        let mut inner = DefaultHasher::new();
+        let mut borrowed = JumpHasher::new_with_hasher(1000, &mut inner as &mut dyn Hasher);
+        "key".hash(&mut borrowed);
+        assert_eq!(borrowed.finish(), expected.finish());
+    }
+
+    #[test]
+    fn test_clone_reset() {
+        let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        "previous".hash(&mut hasher);
+
+        let mut fresh = hasher.clone_reset();
+        let mut expected: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        "key".hash(&mut fresh);
+        "key".hash(&mut expected);
+        assert_eq!(fresh.finish(), expected.finish());
+
+        // The original keeps its state.
+        "key".hash(&mut hasher);
+        let mut continued: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        ("previous", "key").hash(&mut continued);
+        assert_eq!(hasher.finish(), continued.finish());
+    }
+
+    #[test]
+    fn test_finish_checked() {
+        let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        assert_eq!(hasher.finish_checked(), Err(RouteError::Unwritten));
+
+        123456u64.hash(&mut hasher);
+        assert_eq!(hasher.finish_checked(), Ok(hasher.finish() as u32));
+
+        // An empty write still counts, the key may legitimately be empty.
+        hasher.finish_and_reset();
+        assert_eq!(hasher.finish_checked(), Err(RouteError::Unwritten));
+        hasher.write(b"");
+        assert!(hasher.finish_checked().is_ok());
+
+        assert_eq!(
+            hasher.clone_reset().finish_checked(),
+            Err(RouteError::Unwritten)
+        );
+    }
+
+    #[test]
+    fn test_route_bytes() {
+        let hasher = JumpHasher::with_keyed_hash(1000, *b"sixteen byte key");
+        for key in [&b""[..], b"abc", "ключ".as_bytes()] {
+            let mut fresh = JumpHasher::with_keyed_hash(1000, *b"sixteen byte key");
+            fresh.write(key);
+            assert_eq!(hasher.route_bytes(key), fresh.finish() as u32);
+        }
+        assert_eq!(hasher.route_bytes(b"abc"), hasher.route_bytes(b"abc"));
+        assert_eq!(hasher.finish_checked(), Err(RouteError::Unwritten));
+
+        // Bytes continue from the written prefix.
+        let mut prefixed: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        prefixed.write(b"tenant:");
+        let mut full: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        full.write(b"tenant:");
+        full.write(b"abc");
+        assert_eq!(prefixed.route_bytes(b"abc"), full.finish() as u32);
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        let described = hasher.describe();
+        assert!(described.contains("slots=1000"));
+        assert!(described.contains("seed=0"));
+
+        hasher.set_slots(7);
+        assert_eq!(
+            hasher.describe(),
+            "JumpHasher(slots=7, hasher=DefaultHasher, seed=0)"
+        );
+        assert_eq!(
+            JumpHasher::with_fnv(16).describe(),
+            "JumpHasher(slots=16, hasher=Fnv1a, seed=0)"
+        );
+        assert!(BoxedJumpHasher::new_boxed(16, crate::Fnv1a::default())
+            .describe()
+            .starts_with("JumpHasher(slots=16, hasher=Box<dyn "));
+    }
+
+    #[test]
+    fn test_zero_key() {
+        // Zero jumps straight to bucket 2^31, so it sits on bucket 0 below that.
+        for slots in (1..=1u32 << 31).step_by(65_521).chain([1 << 31]) {
+            assert_eq!(hash(0, slots), 0);
+            assert_eq!(hash_with_steps(0, slots), (0, 1));
+        }
+        let top = hash(0, u32::MAX);
+        assert!((1 << 31..u32::MAX).contains(&top));
+        assert_eq!(hash(0, u32::MAX), top);
+
+        // Mixed zero keys spread like any other key.
+        let buckets: std::collections::HashSet<u32> = (0..100)
+            .map(|seed| crate::hash_seeded(0, seed, 1000))
+            .collect();
+        assert!(buckets.len() > 90);
+        assert_ne!(crate::IntoBucket::into_bucket(&0u64, 1000), 0);
+    }
+
+    #[test]
+    fn test_known_values() {
+        assert_eq!(JUMP_LCG_MULTIPLIER, 2862933555777941757);
+        assert_eq!(hash(0, 1), 0);
+        assert_eq!(hash(123456, 1000), 984);
+        assert_eq!(hash(123456, 1), 0);
+        assert_eq!(hash(1, 10), 6);
+        assert_eq!(hash(u64::MAX, 100), 92);
+        assert_eq!(hash(42, 1 << 16), 5747);
+    }
+
+    #[test]
+    fn test_max_slots() {
+        for key in (0..10_000).chain(u64::MAX - 10_000..=u64::MAX) {
+            let (bucket, steps) = hash_with_steps(key, u32::MAX);
+            assert!(bucket < u32::MAX);
+            assert!(steps < 100);
+        }
+    }
+
+    #[test]
+    fn test_max_slots_loop_stays_in_range() {
+        let slots = u32::MAX as i64;
+        for key in (0..5000).chain(u64::MAX - 5000..=u64::MAX) {
+            let (mut state, mut b, mut j) = (key, -1i64, 0i64);
+            while j < slots {
+                b = j;
+                j = next_jump(&mut state, b);
+                // A wrapped target would be negative or move backwards.
+                assert!(j > b, "key {key} jumped from {b} to {j}");
+            }
+            assert!((0..slots).contains(&b));
+            assert_eq!(b as u32, hash(key, u32::MAX));
+        }
+
+        // The widest target jumps from the last bucket with the generator at zero,
+        // `u32::MAX * 2^31` is below `i64::MAX` and exact in `f64`.
+        let mut inverse = JUMP_LCG_MULTIPLIER;
+        for _ in 0..5 {
+            inverse =
+                inverse.wrapping_mul(2u64.wrapping_sub(JUMP_LCG_MULTIPLIER.wrapping_mul(inverse)));
+        }
+        let mut state = u64::MAX.wrapping_mul(inverse);
+        let widest = next_jump(&mut state, slots - 1);
+        assert_eq!(state, 0);
+        assert_eq!(widest, slots << 31);
+    }
+
+    #[test]
+    fn test_hash_bounded() {
+        for key in [0, 1, 42, 123456, u64::MAX] {
+            for slots in [1, 10, 4095, 4096] {
+                assert_eq!(hash_bounded(key, slots, 4096), Ok(hash(key, slots)));
+            }
+            assert_eq!(
+                hash_bounded(key, 4097, 4096),
+                Err(RouteError::SlotsAboveCap {
+                    slots: 4097,
+                    cap: 4096
+                })
+            );
+            assert_eq!(hash_bounded(key, 0, 4096), Err(RouteError::ZeroSlots));
+            assert_eq!(
+                hash_bounded(key, u32::MAX, u32::MAX),
+                Ok(hash(key, u32::MAX))
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_unchecked() {
+        fn checked(key: u64, slots: Slots) -> u32 {
+            // SAFETY: `Slots` is never zero.
+            unsafe { hash_unchecked(key, slots.get()) }
+        }
+
+        for key in (0..1000).chain([u64::MAX]) {
+            for slots in [1, 2, 10, 1000, u32::MAX] {
+                assert_eq!(checked(key, Slots::from(slots)), hash(key, slots));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_guarded() {
+        for slots in (1..2000).chain([u32::MAX - 1, u32::MAX]) {
+            let slots = Slots::from(slots);
+            for key in [0, 1, 42, 123456, u64::MAX] {
+                assert_eq!(hash_guarded(key, slots), hash(key, slots));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_nonzero() {
+        for key in 0..100 {
+            for slots in [1, 2, 10, 1000, u32::MAX] {
+                assert_eq!(hash_nonzero(key, slots).get() - 1, hash(key, slots));
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_writes() {
+        let mut signed: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        let mut unsigned: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        signed.write_i64(-1);
+        unsigned.write_u64(u64::MAX);
+        assert_eq!(signed.finish(), unsigned.finish());
+
+        let mut inner = DefaultHasher::new();
+        inner.write_i32(-42);
+        let mut signed: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        signed.write_i32(-42);
+        assert_eq!(signed.finish(), hash(inner.finish(), 1000) as u64);
+    }
+
+    #[test]
+    fn test_boxed() {
+        #[derive(Default)]
+        struct Xor(u64);
+
+        impl Hasher for Xor {
+            fn finish(&self) -> u64 {
+                self.0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                for byte in bytes {
+                    self.0 = self.0.rotate_left(8) ^ *byte as u64;
+                }
+            }
+        }
+
+        let backends: Vec<fn() -> Box<dyn Hasher>> =
+            vec![|| Box::new(DefaultHasher::new()), || Box::<Xor>::default()];
+        for backend in backends {
+            for key in 0..100 {
+                let mut hasher = BoxedJumpHasher::new_with_hasher(10, backend());
+                key.hash(&mut hasher);
+                assert!(hasher.finish() < 10);
+            }
+        }
+
+        let mut boxed = BoxedJumpHasher::new_boxed(1000, DefaultHasher::new());
+        let mut plain: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        "test".hash(&mut boxed);
+        "test".hash(&mut plain);
+        assert_eq!(boxed.finish(), plain.finish());
+    }
+
+    #[test]
+    fn test_from_build_hasher() {
+        // RandomState is seeded per instance, so only the range can be checked.
+        let random = RandomState::new();
+        for key in 0..100 {
+            let mut hasher = JumpHasher::from_build_hasher(10, &random);
+            key.hash(&mut hasher);
+            assert!(hasher.finish() < 10);
+        }
+
+        let deterministic = BuildHasherDefault::<DefaultHasher>::default();
+        let mut hasher = JumpHasher::from_build_hasher(1000, &deterministic);
+        "test".hash(&mut hasher);
+        assert_eq!(hasher.finish(), 677);
+    }
+
+    #[test]
+    fn test_debug() {
+        let hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        assert_eq!(format!("{hasher:?}"), "JumpHasher { slots: 1000, .. }");
+    }
+
+    #[test]
+    fn test_set_slots() {
+        let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(10);
+        hasher.set_slots(Slots::from(1000));
+        "test".hash(&mut hasher);
+
+        let mut fresh: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        "test".hash(&mut fresh);
+
+        assert_eq!(hasher.finish(), fresh.finish());
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let key = "a key written in two parts".as_bytes();
+        let (head, tail) = key.split_at(key.len() / 2);
+
+        let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        hasher.write(head);
+        let mut snapshot = hasher.snapshot();
+        hasher.write(tail);
+        snapshot.write(tail);
+
+        let mut whole: JumpHasher<DefaultHasher> = JumpHasher::new(1000);
+        whole.write(head);
+        whole.write(tail);
+
+        assert_eq!(hasher.finish(), snapshot.finish());
+        assert_eq!(snapshot.finish(), whole.finish());
+    }
+
     fn check_range<H: Hash>(test: &H) {
         for slots in 0..1000 {
             check_algorithm(slots, test);
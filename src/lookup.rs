@@ -0,0 +1,53 @@
+use crate::{IntoBucket, Slots};
+use std::collections::HashMap;
+
+/// Routes `key` and looks its bucket up in `map`, returning `None` for buckets missing from the map.
+/// The key is routed like [`IntoBucket::into_bucket`].
+///
+/// Usage example:
+/// ```rust
+/// use std::collections::HashMap;
+/// use jumpch::route_into_map;
+///
+/// let map: HashMap<u32, &str> = (0..10).map(|bucket| (bucket, "endpoint")).collect();
+///
+/// assert_eq!(route_into_map(&"key", 10.into(), &map), Some(&"endpoint"))
+/// ```
+pub fn route_into_map<'a, V, K: IntoBucket + ?Sized>(
+    key: &K,
+    slots: Slots,
+    map: &'a HashMap<u32, V>,
+) -> Option<&'a V> {
+    map.get(&key.into_bucket(slots))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{route_into_map, IntoBucket, Slots};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_route_into_map() {
+        let slots = Slots::from(10);
+        let map: HashMap<u32, String> = (0..10)
+            .filter(|bucket| bucket % 2 == 0)
+            .map(|bucket| (bucket, format!("endpoint {bucket}")))
+            .collect();
+
+        let (mut hits, mut misses) = (0, 0);
+        for key in 0..1000u64 {
+            let bucket = key.into_bucket(slots);
+            match route_into_map(&key, slots, &map) {
+                Some(endpoint) => {
+                    assert_eq!(endpoint, &format!("endpoint {bucket}"));
+                    hits += 1;
+                }
+                None => {
+                    assert_eq!(bucket % 2, 1);
+                    misses += 1;
+                }
+            }
+        }
+        assert!(hits > 0 && misses > 0);
+    }
+}
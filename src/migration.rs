@@ -0,0 +1,708 @@
+use crate::seeded::mix64;
+use crate::{hash, hash_domain, Slots};
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// Returns the fraction of keys expected to change buckets when the slot count changes from `from` to `to`.
+/// Jump hashing only moves keys to new buckets on growth, or off removed buckets on shrink,
+/// so it is `1 - min / max` of the two counts.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::expected_movement;
+///
+/// assert_eq!(expected_movement(8.into(), 16.into()), 0.5);
+/// assert_eq!(expected_movement(16.into(), 16.into()), 0.0)
+/// ```
+pub fn expected_movement(from: Slots, to: Slots) -> f64 {
+    let (min, max) = (from.min(to).get(), from.max(to).get());
+    (max - min) as f64 / max as f64
+}
+
+/// Returns whether changing the slot count from `from` to `to` moves at most `max_fraction` of the keys,
+/// per [`expected_movement`], to gate risky reshards automatically.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::is_safe_transition;
+///
+/// assert!(is_safe_transition(100.into(), 105.into(), 0.1));
+/// assert!(!is_safe_transition(100.into(), 50.into(), 0.1))
+/// ```
+pub fn is_safe_transition(from: Slots, to: Slots, max_fraction: f64) -> bool {
+    expected_movement(from, to) <= max_fraction
+}
+
+/// Returns the buckets of `key` under both the `old` and the `new` slot count,
+/// for reading from the old layout and writing to the new one during a migration.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{dual_route, hash};
+///
+/// assert_eq!(dual_route(123456, 10.into(), 1000.into()), (hash(123456, 10), hash(123456, 1000)))
+/// ```
+pub fn dual_route(key: u64, old: Slots, new: Slots) -> (u32, u32) {
+    (hash(key, old), hash(key, new))
+}
+
+/// Returns bucket for `key` over the `new` layout and whether it differs from its bucket over the `old` one,
+/// for acting only on keys which move during a rebalance.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, route_changed};
+///
+/// let (bucket, changed) = route_changed(123456, 10.into(), 1000.into());
+/// assert_eq!(bucket, hash(123456, 1000));
+/// assert_eq!(changed, hash(123456, 10) != bucket)
+/// ```
+pub fn route_changed(key: u64, old: Slots, new: Slots) -> (u32, bool) {
+    let (old, new) = dual_route(key, old, new);
+    (new, old != new)
+}
+
+/// Routes `key` over `total` buckets of which only the `active` sub-range is serving, e.g. during a phased
+/// rollout of shards. A key keeps its bucket if it is active, and is jump hashed over the active buckets otherwise,
+/// so the result is uniform over `active` and, for `active` starting at 0, equals [`hash`] over `active.end`.
+///
+/// Growing `active` by raising its end moves keys only onto the newly active buckets,
+/// and ends on the plain [`hash`] over `total` once the whole range is active.
+/// Moving the start of `active` reshuffles the keys routed by the fallback.
+///
+/// Panics if `active` is empty or ends beyond `total`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_subrange};
+///
+/// assert!((2..5).contains(&hash_subrange(123456, 10.into(), 2..5)));
+/// assert_eq!(hash_subrange(123456, 10.into(), 0..10), hash(123456, 10))
+/// ```
+pub fn hash_subrange(key: u64, total: Slots, active: Range<u32>) -> u32 {
+    assert!(!active.is_empty(), "active buckets must not be empty");
+    assert!(
+        active.end <= total.get(),
+        "active buckets {}..{} exceed {} slots",
+        active.start,
+        active.end,
+        total.get()
+    );
+    let bucket = hash(key, total);
+    if active.contains(&bucket) {
+        bucket
+    } else {
+        active.start + hash(key, active.end - active.start)
+    }
+}
+
+/// Routes `key` over the `new` layout if it falls into the first `percent` of keys, and over the `old` one otherwise,
+/// for canary rollouts shifting a growing share of traffic. Keys are split by a hash of the key independent
+/// of both layouts, and raising `percent` only moves keys to the new layout, never back.
+/// A `percent` of 100 or more routes every key over `new`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, route_percentile};
+///
+/// assert_eq!(route_percentile(123456, 10.into(), 20.into(), 0), hash(123456, 10));
+/// assert_eq!(route_percentile(123456, 10.into(), 20.into(), 100), hash(123456, 20))
+/// ```
+pub fn route_percentile(key: u64, old: Slots, new: Slots, percent: u8) -> u32 {
+    if hash_domain("route_percentile", key, Slots::from(100)) < percent as u32 {
+        hash(key, new)
+    } else {
+        hash(key, old)
+    }
+}
+
+/// Routes `key` over `slots`, but redirects a `new_bucket_bias` share of all keys to the newest bucket `slots - 1`,
+/// to warm up the cache of a freshly added bucket faster. The redirected keys are picked by a hash of the key,
+/// so a key is always redirected or never for a given bias.
+///
+/// This deliberately breaks the uniform distribution: the newest bucket gets about
+/// `bias + (1 - bias) / slots` of the keys. Route with [`hash`] again once the bucket is warm.
+///
+/// Panics if `new_bucket_bias` is not within `0.0..=1.0`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, route_warmup};
+///
+/// assert_eq!(route_warmup(123456, 10.into(), 0.0), hash(123456, 10));
+/// assert_eq!(route_warmup(123456, 10.into(), 1.0), 9)
+/// ```
+pub fn route_warmup(key: u64, slots: Slots, new_bucket_bias: f64) -> u32 {
+    assert!(
+        (0.0..=1.0).contains(&new_bucket_bias),
+        "new bucket bias must be within 0.0..=1.0, got {new_bucket_bias}"
+    );
+    // 53 bits of the mixed key are a uniform float in 0..1.
+    let sample = (mix64(key ^ WARMUP_SEED) >> 11) as f64 / (1u64 << 53) as f64;
+    if sample < new_bucket_bias {
+        slots.get() - 1
+    } else {
+        hash(key, slots)
+    }
+}
+
+/// Separates the warm-up sample of a key from the other mixes of it.
+const WARMUP_SEED: u64 = 0x5741_524d_5550_0001;
+
+/// Returns every key of `keys` that changes buckets when the slot count changes from `from` to `to`,
+/// as `(key, old_bucket, new_bucket)`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::plan_migration;
+///
+/// let movers = plan_migration(&[1, 2, 3, 4, 5], 3.into(), 4.into());
+///
+/// assert!(movers.iter().all(|(_, _, new)| *new == 3))
+/// ```
+pub fn plan_migration(keys: &[u64], from: Slots, to: Slots) -> Vec<(u64, u32, u32)> {
+    let expected_movers = (keys.len() as f64 * expected_movement(from, to)).ceil() as usize;
+    plan_migration_with_capacity(keys, from, to, expected_movers)
+}
+
+/// Same as [`plan_migration`], but reserves room for `expected_movers` movers upfront.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{plan_migration, plan_migration_with_capacity};
+///
+/// let keys: Vec<u64> = (0..1000).collect();
+///
+/// assert_eq!(
+///     plan_migration_with_capacity(&keys, 3.into(), 4.into(), 250),
+///     plan_migration(&keys, 3.into(), 4.into())
+/// )
+/// ```
+pub fn plan_migration_with_capacity(
+    keys: &[u64],
+    from: Slots,
+    to: Slots,
+    expected_movers: usize,
+) -> Vec<(u64, u32, u32)> {
+    let mut movers = Vec::with_capacity(expected_movers);
+    movers.extend(
+        keys.iter()
+            .map(|key| (*key, hash(*key, from), hash(*key, to)))
+            .filter(|(_, old, new)| old != new),
+    );
+    movers
+}
+
+/// Returns at most `budget` of the [`plan_migration`] movers of `keys`, in key order, and whether more movers remain,
+/// for throttled migrations moving a bounded number of keys per batch.
+/// To resume, call it again with the keys after the last returned mover.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::bounded_reshard;
+///
+/// let keys: Vec<u64> = (0..1000).collect();
+/// let (batch, more) = bounded_reshard(&keys, 3.into(), 4.into(), 10);
+///
+/// assert_eq!(batch.len(), 10);
+/// assert!(more)
+/// ```
+pub fn bounded_reshard(
+    keys: &[u64],
+    from: Slots,
+    to: Slots,
+    budget: usize,
+) -> (Vec<(u64, u32, u32)>, bool) {
+    let mut movers = keys
+        .iter()
+        .map(|key| (*key, hash(*key, from), hash(*key, to)))
+        .filter(|(_, old, new)| old != new);
+    let batch: Vec<_> = movers.by_ref().take(budget).collect();
+    (batch, movers.next().is_some())
+}
+
+/// Returns the scale-down plan of shrinking from `from` to `to` slots as `(key, old_bucket, new_bucket)`
+/// for every key of `keys`, in order. Only keys of the removed buckets `to..from` change buckets,
+/// they are merged into the remaining ones, every other key keeps its bucket.
+///
+/// Panics if `to` is greater than `from`, growing is planned with [`plan_migration`].
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::merge_plan;
+///
+/// let plan = merge_plan(&[1, 2, 3, 4, 5], 5.into(), 3.into());
+///
+/// assert!(plan.iter().all(|(_, old, new)| old == new || *old >= 3))
+/// ```
+pub fn merge_plan(keys: &[u64], from: Slots, to: Slots) -> Vec<(u64, u32, u32)> {
+    assert!(
+        to <= from,
+        "merge plan must shrink, got {} slots growing to {}",
+        from.get(),
+        to.get()
+    );
+    keys.iter()
+        .map(|key| {
+            let (old, new) = dual_route(*key, from, to);
+            (*key, old, new)
+        })
+        .collect()
+}
+
+/// `SnapshotDiff` is the full picture of an incremental migration between two key set snapshots,
+/// built by [`snapshot_diff`]. Every list keeps the order of the keys in their snapshot.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    /// Keys only in the new snapshot, as `(key, new_bucket)`
+    pub added: Vec<(u64, u32)>,
+    /// Keys only in the old snapshot, as `(key, old_bucket)`
+    pub removed: Vec<(u64, u32)>,
+    /// Keys in both snapshots that changed buckets, as `(key, old_bucket, new_bucket)`
+    pub moved: Vec<(u64, u32, u32)>,
+}
+
+/// Compares the `old_keys` snapshot routed over `old_slots` to the `new_keys` snapshot routed over `new_slots`,
+/// for migrations where both the key set and the slot count changed.
+/// Retained keys that stay in their bucket are not reported.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, snapshot_diff};
+///
+/// let diff = snapshot_diff(&[1, 2, 3], 10.into(), &[2, 3, 4], 20.into());
+///
+/// assert_eq!(diff.added, vec![(4, hash(4, 20))]);
+/// assert_eq!(diff.removed, vec![(1, hash(1, 10))])
+/// ```
+pub fn snapshot_diff(
+    old_keys: &[u64],
+    old_slots: Slots,
+    new_keys: &[u64],
+    new_slots: Slots,
+) -> SnapshotDiff {
+    let old: HashSet<u64> = old_keys.iter().copied().collect();
+    let new: HashSet<u64> = new_keys.iter().copied().collect();
+
+    let mut diff = SnapshotDiff::default();
+    for key in new_keys {
+        let bucket = hash(*key, new_slots);
+        if !old.contains(key) {
+            diff.added.push((*key, bucket));
+            continue;
+        }
+        let old_bucket = hash(*key, old_slots);
+        if old_bucket != bucket {
+            diff.moved.push((*key, old_bucket, bucket));
+        }
+    }
+    diff.removed = old_keys
+        .iter()
+        .filter(|key| !new.contains(key))
+        .map(|key| (*key, hash(*key, old_slots)))
+        .collect();
+    diff
+}
+
+/// Returns the keys of `keys` that move when the slot count shrinks from `slots` to `slots - 1`.
+/// Jump hashing guarantees those are exactly the keys of the removed top bucket `slots - 1`,
+/// they spread over the remaining buckets and every other key stays put.
+///
+/// Panics if `slots` is one, there is nothing to shrink to.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, shrink_movers};
+///
+/// let movers = shrink_movers(&[1, 2, 3, 4, 5], 4.into());
+///
+/// assert!(movers.iter().all(|key| hash(*key, 4) == 3))
+/// ```
+pub fn shrink_movers(keys: &[u64], slots: Slots) -> Vec<u64> {
+    let to = Slots::new(slots.get() - 1).expect("can't shrink below one slot");
+    keys.iter()
+        .copied()
+        .filter(|key| hash(*key, slots) != hash(*key, to))
+        .collect()
+}
+
+/// Returns the `from × to` table of key movements, where `matrix[i][j]` counts the keys of `keys`
+/// routed to bucket `i` under `from` slots and to bucket `j` under `to` slots.
+/// Keys that stay on their bucket fill the diagonal.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::migration_matrix;
+///
+/// let keys: Vec<u64> = (0..1000).collect();
+/// let matrix = migration_matrix(&keys, 3.into(), 4.into());
+///
+/// // Growing never moves keys between the old buckets.
+/// assert_eq!(matrix[0][1], 0);
+/// assert_eq!(matrix.iter().flatten().sum::<u64>(), 1000)
+/// ```
+pub fn migration_matrix(keys: &[u64], from: Slots, to: Slots) -> Vec<Vec<u64>> {
+    let mut matrix = vec![vec![0; to.get() as usize]; from.get() as usize];
+    for (old, new) in keys.iter().map(|key| dual_route(*key, from, to)) {
+        matrix[old as usize][new as usize] += 1;
+    }
+    matrix
+}
+
+/// Calls `f` for every key of `keys` that moves to the new bucket `from.get()` when the slot count grows by one.
+/// It is the allocation-free form of [`plan_migration`] for single-slot growth, where every mover lands on the new bucket.
+/// Nothing moves if `from` is already `u32::MAX`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, on_grow};
+///
+/// let mut moved = Vec::new();
+/// on_grow(&[1, 2, 3, 4, 5], 3.into(), |key| moved.push(key));
+///
+/// assert!(moved.iter().all(|key| hash(*key, 4) == 3))
+/// ```
+pub fn on_grow<F: FnMut(u64)>(keys: &[u64], from: Slots, mut f: F) {
+    let to = match Slots::new(from.get().wrapping_add(1)) {
+        Some(to) => to,
+        None => return,
+    };
+    keys.iter()
+        .copied()
+        .filter(|key| hash(*key, to) == from.get())
+        .for_each(&mut f);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bounded_reshard, distribution, dual_route, expected_movement, hash, hash_subrange,
+        is_safe_transition, merge_plan, migration_matrix, on_grow, plan_migration,
+        plan_migration_with_capacity, route_changed, route_percentile, route_warmup, shrink_movers,
+        snapshot_diff, Slots,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_expected_movement() {
+        assert_eq!(expected_movement(Slots::from(4), Slots::from(5)), 0.2);
+        assert_eq!(expected_movement(Slots::from(5), Slots::from(4)), 0.2);
+        assert_eq!(expected_movement(Slots::from(1), Slots::from(1)), 0.0);
+    }
+
+    #[test]
+    fn test_dual_route() {
+        let (old, new) = (Slots::from(10), Slots::from(13));
+        for key in 0..1000 {
+            assert_eq!(dual_route(key, old, new), (hash(key, old), hash(key, new)));
+        }
+    }
+
+    #[test]
+    fn test_route_changed() {
+        let (old, new) = (Slots::from(10), Slots::from(20));
+        let moving = (0..)
+            .find(|key| hash(*key, old) != hash(*key, new))
+            .unwrap();
+        let staying = (0..)
+            .find(|key| hash(*key, old) == hash(*key, new))
+            .unwrap();
+
+        assert_eq!(route_changed(moving, old, new), (hash(moving, new), true));
+        assert_eq!(
+            route_changed(staying, old, new),
+            (hash(staying, new), false)
+        );
+        assert_eq!(route_changed(moving, old, old), (hash(moving, old), false));
+    }
+
+    #[test]
+    fn test_hash_subrange() {
+        let total = Slots::from(20);
+        for key in 0..10_000 {
+            assert!((3..8).contains(&hash_subrange(key, total, 3..8)));
+            assert_eq!(hash_subrange(key, total, 0..8), hash(key, 8));
+            assert_eq!(hash_subrange(key, total, 0..20), hash(key, total));
+        }
+
+        for end in 4..20 {
+            let mut moved = 0u32;
+            for key in 0..10_000 {
+                let (old, new) = (
+                    hash_subrange(key, total, 3..end),
+                    hash_subrange(key, total, 3..end + 1),
+                );
+                if old != new {
+                    assert_eq!(new, end);
+                    moved += 1;
+                }
+            }
+            let expected = 10_000 / (end + 1 - 3);
+            assert!(
+                moved.abs_diff(expected) < expected / 4 + 50,
+                "{end}: {moved}"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "active buckets 5..21 exceed 20 slots")]
+    fn test_hash_subrange_out_of_range() {
+        hash_subrange(1, 20.into(), 5..21);
+    }
+
+    #[test]
+    fn test_plan_migration() {
+        let keys: Vec<u64> = (0..10_000).collect();
+        let (from, to) = (Slots::from(10), Slots::from(12));
+        let movers = plan_migration(&keys, from, to);
+
+        for (key, old, new) in &movers {
+            assert_eq!(*old, hash(*key, from));
+            assert_eq!(*new, hash(*key, to));
+            assert!(*new >= 10);
+        }
+        let moved = movers.len() as f64 / keys.len() as f64;
+        assert!((moved - expected_movement(from, to)).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_with_capacity_matches() {
+        let keys: Vec<u64> = (0..10_000).map(|i| i * 31).collect();
+        for (from, to) in [(3, 4), (10, 7), (8, 8)] {
+            let (from, to) = (Slots::from(from), Slots::from(to));
+            let plan = plan_migration(&keys, from, to);
+            for capacity in [0, 10, 100_000] {
+                let with_capacity = plan_migration_with_capacity(&keys, from, to, capacity);
+                assert_eq!(with_capacity, plan);
+                assert!(with_capacity.capacity() >= capacity);
+            }
+        }
+    }
+
+    #[test]
+    fn test_on_grow_matches_plan() {
+        let keys: Vec<u64> = (0..10_000).map(|i| i * 7).collect();
+        for from in [1, 3, 10, 99] {
+            let from = Slots::from(from);
+            let mut moved = HashSet::new();
+            on_grow(&keys, from, |key| {
+                moved.insert(key);
+            });
+
+            let planned: HashSet<u64> = plan_migration(&keys, from, from.saturating_add(1))
+                .into_iter()
+                .map(|(key, _, _)| key)
+                .collect();
+            assert!(!moved.is_empty());
+            assert_eq!(moved, planned);
+        }
+
+        let mut called = false;
+        on_grow(&keys, Slots::from(u32::MAX), |_| called = true);
+        assert!(!called);
+    }
+
+    #[test]
+    fn test_migration_matrix() {
+        let keys: Vec<u64> = (0..5000).map(|i| i * 13).collect();
+        for (from, to) in [(5, 8), (8, 5), (6, 6)] {
+            let (from, to) = (Slots::from(from), Slots::from(to));
+            let matrix = migration_matrix(&keys, from, to);
+            assert_eq!(matrix.len(), from.get() as usize);
+
+            let rows: Vec<u64> = matrix.iter().map(|row| row.iter().sum()).collect();
+            let columns: Vec<u64> = (0..to.get() as usize)
+                .map(|j| matrix.iter().map(|row| row[j]).sum())
+                .collect();
+            assert_eq!(rows, distribution(keys.iter().copied(), from));
+            assert_eq!(columns, distribution(keys.iter().copied(), to));
+
+            let min = from.min(to).get() as usize;
+            for (i, row) in matrix.iter().enumerate().take(min) {
+                for (j, moved) in row.iter().enumerate().take(min) {
+                    assert!(i == j || *moved == 0, "{i} -> {j} moved {moved} keys");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_plan() {
+        let keys: Vec<u64> = (0..10_000).collect();
+        let (from, to) = (Slots::from(5), Slots::from(3));
+        let plan = merge_plan(&keys, from, to);
+        assert_eq!(plan.len(), keys.len());
+
+        let movers: Vec<_> = plan.iter().filter(|(_, old, new)| old != new).collect();
+        for (key, old, new) in &plan {
+            assert_eq!((*old, *new), dual_route(*key, from, to));
+            assert_eq!(old != new, *old >= 3);
+        }
+        assert_eq!(
+            movers.into_iter().copied().collect::<Vec<_>>(),
+            plan_migration(&keys, from, to)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "merge plan must shrink, got 3 slots growing to 5")]
+    fn test_merge_plan_growth() {
+        merge_plan(&[1], Slots::from(3), Slots::from(5));
+    }
+
+    #[test]
+    fn test_is_safe_transition() {
+        let (from, small_growth) = (Slots::from(100), Slots::from(105));
+        assert!(is_safe_transition(from, small_growth, 0.05));
+        assert!(!is_safe_transition(from, small_growth, 0.04));
+
+        assert!(!is_safe_transition(from, Slots::from(20), 0.1));
+        assert!(is_safe_transition(from, Slots::from(20), 0.8));
+        assert!(is_safe_transition(from, from, 0.0));
+    }
+
+    #[test]
+    fn test_route_percentile() {
+        let (old, new) = (Slots::from(10), Slots::from(1000));
+        let on_new = |percent: u8| -> Vec<u64> {
+            (0..10_000)
+                .filter(|key| {
+                    let bucket = route_percentile(*key, old, new, percent);
+                    assert!(bucket == hash(*key, old) || bucket == hash(*key, new));
+                    // Keys routed to the same bucket by both layouts count as old.
+                    bucket == hash(*key, new) && bucket != hash(*key, old)
+                })
+                .collect()
+        };
+
+        let mut previous = Vec::new();
+        for percent in [0, 1, 10, 25, 50, 90, 100] {
+            let current = on_new(percent);
+            let expected = 100 * percent as usize;
+            assert!(
+                current.len().abs_diff(expected) <= expected / 10 + 50,
+                "{percent}%: {}",
+                current.len()
+            );
+            assert!(previous.iter().all(|key| current.contains(key)));
+            previous = current;
+        }
+        assert_eq!(on_new(255), on_new(100));
+    }
+
+    #[test]
+    fn test_route_warmup() {
+        let slots = Slots::from(10);
+        let newest = |bias: f64| {
+            (0..100_000u64)
+                .filter(|key| route_warmup(*key, slots, bias) == 9)
+                .count()
+        };
+
+        assert_eq!(newest(0.0), distribution(0..100_000, slots)[9] as usize);
+        for bias in [0.1, 0.3] {
+            let expected = 100_000.0 * (bias + (1.0 - bias) / 10.0);
+            assert!((newest(bias) as f64 - expected).abs() < expected * 0.05);
+            assert!(newest(bias) > 10_000);
+        }
+        assert_eq!(newest(1.0), 100_000);
+
+        // Keys not redirected keep their regular bucket.
+        for key in 0..10_000 {
+            let bucket = route_warmup(key, slots, 0.5);
+            assert!(bucket == 9 || bucket == hash(key, slots));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "new bucket bias must be within 0.0..=1.0, got 1.5")]
+    fn test_route_warmup_bias_out_of_range() {
+        route_warmup(1, Slots::from(10), 1.5);
+    }
+
+    #[test]
+    fn test_bounded_reshard() {
+        let keys: Vec<u64> = (0..1000).collect();
+        let (from, to) = (Slots::from(10), Slots::from(12));
+        let all = plan_migration(&keys, from, to);
+
+        for budget in [0, 1, 10, all.len() - 1, all.len(), all.len() + 1] {
+            let (batch, more) = bounded_reshard(&keys, from, to, budget);
+            assert!(batch.len() <= budget);
+            assert_eq!(batch, all[..budget.min(all.len())]);
+            assert_eq!(more, budget < all.len());
+        }
+
+        // Resuming after the last mover of every batch walks through all of them.
+        let (mut rest, mut moved) = (&keys[..], Vec::new());
+        loop {
+            let (batch, more) = bounded_reshard(rest, from, to, 7);
+            moved.extend_from_slice(&batch);
+            if !more {
+                break;
+            }
+            let last = batch.last().unwrap().0;
+            rest = &rest[rest.iter().position(|key| *key == last).unwrap() + 1..];
+        }
+        assert_eq!(moved, all);
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        let old_keys: Vec<u64> = (0..1000).collect();
+        let new_keys: Vec<u64> = (500..1500).collect();
+        let (old_slots, new_slots) = (Slots::from(10), Slots::from(16));
+
+        let diff = snapshot_diff(&old_keys, old_slots, &new_keys, new_slots);
+
+        let added: Vec<_> = (1000..1500)
+            .map(|key| (key, hash(key, new_slots)))
+            .collect();
+        let removed: Vec<_> = (0..500).map(|key| (key, hash(key, old_slots))).collect();
+        assert_eq!(diff.added, added);
+        assert_eq!(diff.removed, removed);
+
+        assert!(!diff.moved.is_empty());
+        assert_eq!(
+            diff.moved,
+            plan_migration(&old_keys[500..], old_slots, new_slots)
+        );
+        for (_, _, new) in &diff.moved {
+            assert!(*new >= 10);
+        }
+
+        assert_eq!(
+            snapshot_diff(&old_keys, old_slots, &old_keys, old_slots),
+            Default::default()
+        );
+    }
+
+    #[test]
+    fn test_shrink_movers() {
+        let keys: Vec<u64> = (0..10_000).map(|i| i * 17).collect();
+        for slots in [2, 3, 10, 64] {
+            let slots = Slots::from(slots);
+            let top = slots.get() - 1;
+            let on_top: Vec<u64> = keys
+                .iter()
+                .copied()
+                .filter(|key| hash(*key, slots) == top)
+                .collect();
+
+            let movers = shrink_movers(&keys, slots);
+
+            assert!(!movers.is_empty());
+            assert_eq!(movers, on_top);
+            for key in &movers {
+                assert!(hash(*key, top) < top);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "can't shrink below one slot")]
+    fn test_shrink_single_slot() {
+        shrink_movers(&[1], Slots::from(1));
+    }
+}
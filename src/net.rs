@@ -0,0 +1,81 @@
+use crate::fnv::fnv1a;
+use crate::{hash, Slots};
+use std::net::{IpAddr, SocketAddr};
+
+/// Routes an IP address independently of the std `Hash` implementation of addresses.
+/// The address is serialized as a family byte (`4` or `6`) followed by its octets in network order,
+/// and the bytes are hashed with 64-bit FNV-1a, so the bucket of an address never changes between versions.
+///
+/// Usage example:
+/// ```rust
+/// use std::net::{IpAddr, Ipv4Addr};
+/// use jumpch::hash_ip;
+///
+/// let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1));
+///
+/// assert_eq!(hash_ip(ip, 1000), hash_ip(ip, 1000))
+/// ```
+pub fn hash_ip<S: Into<Slots>>(ip: IpAddr, slots: S) -> u32 {
+    let mut bytes = [0u8; 17];
+    let len = match ip {
+        IpAddr::V4(ip) => {
+            bytes[0] = 4;
+            bytes[1..5].copy_from_slice(&ip.octets());
+            5
+        }
+        IpAddr::V6(ip) => {
+            bytes[0] = 6;
+            bytes[1..17].copy_from_slice(&ip.octets());
+            17
+        }
+    };
+    hash(fnv1a(&bytes[..len]), slots)
+}
+
+/// Routes the IP address of a socket address, ignoring the port, see [`hash_ip`].
+///
+/// Usage example:
+/// ```rust
+/// use std::net::SocketAddr;
+/// use jumpch::{hash_ip, hash_socket_ip};
+///
+/// let addr: SocketAddr = "10.0.0.1:8080".parse().unwrap();
+///
+/// assert_eq!(hash_socket_ip(addr, 1000), hash_ip(addr.ip(), 1000))
+/// ```
+pub fn hash_socket_ip<S: Into<Slots>>(addr: SocketAddr, slots: S) -> u32 {
+    hash_ip(addr.ip(), slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash_ip, hash_socket_ip};
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    #[test]
+    fn test_v4() {
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1));
+        for slots in 1..1000 {
+            assert_eq!(hash_ip(ip, slots), hash_ip(ip, slots));
+            assert!(hash_ip(ip, slots) < slots);
+        }
+        assert_eq!(hash_ip(ip, 1000), 526);
+    }
+
+    #[test]
+    fn test_v6() {
+        let ip = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        for slots in 1..1000 {
+            assert_eq!(hash_ip(ip, slots), hash_ip(ip, slots));
+            assert!(hash_ip(ip, slots) < slots);
+        }
+        assert_eq!(hash_ip(ip, 1000), 758);
+    }
+
+    #[test]
+    fn test_socket_ignores_port() {
+        let a: SocketAddr = "10.0.0.1:80".parse().unwrap();
+        let b: SocketAddr = "10.0.0.1:443".parse().unwrap();
+        assert_eq!(hash_socket_ip(a, 1000), hash_socket_ip(b, 1000));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{hash, Slots};
+use std::collections::HashMap;
+
+/// `PinnedSharder` routes keys with jump hashing, except for pinned keys that always go to their pinned bucket,
+/// e.g. a tenant living on a dedicated shard.
+///
+/// Example:
+/// ```rust
+/// use jumpch::{hash, PinnedSharder};
+///
+/// let mut sharder = PinnedSharder::new(10.into());
+/// sharder.pin(42, 7);
+///
+/// assert_eq!(sharder.route(42), 7);
+/// assert_eq!(sharder.route(43), hash(43, 10))
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PinnedSharder {
+    slots: Slots,
+    pins: HashMap<u64, u32>,
+}
+
+impl PinnedSharder {
+    /// Create new PinnedSharder over `slots` without pins
+    pub fn new(slots: Slots) -> Self {
+        Self {
+            slots,
+            pins: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of buckets
+    pub fn slots(&self) -> Slots {
+        self.slots
+    }
+
+    /// Returns the pinned keys with their buckets
+    pub fn pins(&self) -> &HashMap<u64, u32> {
+        &self.pins
+    }
+
+    /// Pins `key` to `bucket`, returning the previous pin of the key.
+    ///
+    /// Panics if `bucket` is out of range for the slots.
+    pub fn pin(&mut self, key: u64, bucket: u32) -> Option<u32> {
+        assert!(
+            bucket < self.slots.get(),
+            "bucket {bucket} is out of range for {} slots",
+            self.slots.get()
+        );
+        self.pins.insert(key, bucket)
+    }
+
+    /// Removes the pin of `key`, returning its bucket if it was pinned
+    pub fn unpin(&mut self, key: u64) -> Option<u32> {
+        self.pins.remove(&key)
+    }
+
+    /// Returns the pinned bucket of `key`, or its jump hash bucket if it isn't pinned
+    pub fn route(&self, key: u64) -> u32 {
+        match self.pins.get(&key) {
+            Some(bucket) => *bucket,
+            None => hash(key, self.slots),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, PinnedSharder, Slots};
+
+    #[test]
+    fn test_pinned_route() {
+        let slots = Slots::from(16);
+        let mut sharder = PinnedSharder::new(slots);
+        let pinned: Vec<u64> = (0..100).filter(|key| key % 10 == 0).collect();
+        for key in &pinned {
+            assert_eq!(sharder.pin(*key, 15), None);
+        }
+
+        for key in 0..100 {
+            if pinned.contains(&key) {
+                assert_eq!(sharder.route(key), 15);
+            } else {
+                assert_eq!(sharder.route(key), hash(key, slots));
+            }
+        }
+
+        assert_eq!(sharder.pin(0, 3), Some(15));
+        assert_eq!(sharder.route(0), 3);
+        assert_eq!(sharder.unpin(0), Some(3));
+        assert_eq!(sharder.unpin(0), None);
+        assert_eq!(sharder.route(0), hash(0, slots));
+        assert_eq!(sharder.pins().len(), pinned.len() - 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "bucket 16 is out of range for 16 slots")]
+    fn test_pin_out_of_range() {
+        PinnedSharder::new(Slots::from(16)).pin(1, 16);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{hash, Slots, JUMP_LCG_MULTIPLIER};
+
+/// The arithmetic the jump loop computes its targets with, see [`hash_mode`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Precision {
+    /// `f64` arithmetic, exactly [`hash`]. This is the default everywhere else in the crate.
+    Fast,
+    /// Integer arithmetic, the jump targets are the exact floors of the algorithm's formula.
+    Exact,
+}
+
+/// Routes `key` with the jump target arithmetic chosen by `mode`.
+///
+/// Both modes agree for practically every key. `f64` rounds the jump targets though, so a key whose target
+/// falls within rounding error of an integer may land on a different bucket with [`Precision::Exact`].
+/// Such keys are rare at any slot count, none show up in the tests, but don't mix both modes in one deployment.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_mode, Precision};
+///
+/// assert_eq!(hash_mode(123456, 1000.into(), Precision::Fast), hash(123456, 1000));
+/// assert_eq!(hash_mode(123456, 1000.into(), Precision::Exact), hash(123456, 1000))
+/// ```
+pub fn hash_mode(key: u64, slots: Slots, mode: Precision) -> u32 {
+    match mode {
+        Precision::Fast => hash(key, slots),
+        Precision::Exact => hash_exact(key, slots.get()),
+    }
+}
+
+fn hash_exact(mut key: u64, slots: u32) -> u32 {
+    let (mut b, mut j) = (0u64, 0u64);
+    while j < slots as u64 {
+        b = j;
+        key = key.wrapping_mul(JUMP_LCG_MULTIPLIER).wrapping_add(1);
+        // (b + 1) is at most 2^32, so the numerator fits into u64.
+        j = ((b + 1) << 31) / ((key >> 33) + 1);
+    }
+    b as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, hash_mode, Precision, Slots};
+
+    #[test]
+    fn test_modes_agree() {
+        for slots in (1..=100_000).step_by(997) {
+            let slots = Slots::from(slots);
+            for key in 0..100 {
+                assert_eq!(
+                    hash_mode(key, slots, Precision::Exact),
+                    hash_mode(key, slots, Precision::Fast)
+                );
+            }
+        }
+        assert_eq!(hash_mode(123456, Slots::from(1000), Precision::Exact), 984);
+    }
+
+    #[test]
+    fn test_extreme_slots() {
+        // Rounding could only matter for huge targets, the sampled keys still agree at the top of the range.
+        for slots in [1 << 30, u32::MAX] {
+            let slots = Slots::from(slots);
+            for key in 0..10_000 {
+                let exact = hash_mode(key, slots, Precision::Exact);
+                assert!(exact < slots.get());
+                assert_eq!(exact, hash(key, slots));
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::Slots;
+use proptest::strategy::Strategy;
+
+/// Generates valid slot counts in `1..=max`, for property testing routing code.
+///
+/// Panics if `max` is zero.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, slots_strategy};
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     fn bucket_in_range(key in any::<u64>(), slots in slots_strategy(1000)) {
+///         prop_assert!(hash(key, slots) < slots.get());
+///     }
+/// }
+///
+/// bucket_in_range();
+/// ```
+pub fn slots_strategy(max: u32) -> impl Strategy<Value = Slots> {
+    assert!(max > 0, "max slots must be greater than 0");
+    (1..=max).prop_map(Slots::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, slots_strategy};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_slots_strategy(slots in slots_strategy(100)) {
+            prop_assert!((1..=100).contains(&slots.get()));
+        }
+
+        #[test]
+        fn test_bucket_in_range(key in any::<u64>(), slots in slots_strategy(u32::MAX)) {
+            prop_assert!(hash(key, slots) < slots.get());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "max slots must be greater than 0")]
+    fn test_zero_max() {
+        let _ = slots_strategy(0);
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{check, hash, stable_v1, Slots};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn hash_invariants(key in any::<u64>(), slots in 1..=u32::MAX) {
+        check(key, slots);
+    }
+
+    #[test]
+    fn hash_invariants_small_slots(key in any::<u64>(), slots in 1..=1024u32) {
+        check(key, slots);
+    }
+
+    #[test]
+    fn u32_and_slots_agree(key in any::<u64>(), slots in 1..=u32::MAX) {
+        prop_assert_eq!(hash(key, slots), hash(key, Slots::from(slots)));
+    }
+
+    #[test]
+    fn hash_is_deterministic(key in any::<u64>(), slots in 1..=u32::MAX) {
+        let bucket = hash(key, slots);
+        prop_assert_eq!(hash(key, slots), bucket);
+        prop_assert_eq!(stable_v1::hash(key, slots), bucket);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::hash;
+
+/// The reference `JumpConsistentHash` from "A Fast, Minimal Memory, Consistent Hash Algorithm"
+/// by Lamping and Veach, transcribed line by line from the C++ listing of the paper.
+/// It shares no code with [`hash`], so optimizations of the crate can't drift unnoticed.
+fn reference_hash(mut key: u64, num_buckets: u32) -> u32 {
+    let (mut b, mut j): (i64, i64) = (-1, 0);
+    while j < num_buckets as i64 {
+        b = j;
+        key = key.wrapping_mul(2862933555777941757).wrapping_add(1);
+        j = ((b + 1) as f64 * ((1i64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    b as u32
+}
+
+/// A small xorshift generator, so the sweep covers keys of every magnitude.
+fn sweep() -> impl Iterator<Item = u64> {
+    let mut state = 0x2545f4914f6cdd1d_u64;
+    std::iter::from_fn(move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        Some(state)
+    })
+}
+
+#[test]
+fn test_matches_reference() {
+    let keys: Vec<u64> = (0..200)
+        .chain([u64::MAX])
+        .chain(sweep().take(2000))
+        .collect();
+    let slot_counts = (1..=300)
+        .chain([
+            1000,
+            4096,
+            65_535,
+            65_536,
+            1 << 20,
+            i32::MAX as u32,
+            1 << 31,
+        ])
+        .chain([u32::MAX - 1, u32::MAX])
+        .chain(sweep().take(100).map(|x| (x >> 32) as u32 | 1));
+
+    for slots in slot_counts {
+        for key in &keys {
+            assert_eq!(
+                hash(*key, slots),
+                reference_hash(*key, slots),
+                "key {key}, slots {slots}"
+            );
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::check;
+
+/// Inputs that once broke an invariant or stress an extreme of the algorithm.
+/// Every case found by property testing or fuzzing becomes one `(key, slots)` entry here.
+const CASES: &[(u64, u32)] = &[
+    // The widest slot count, every jump target must saturate into range.
+    (u64::MAX, u32::MAX),
+    (0, u32::MAX),
+    // Key 0 stays in bucket 0 up to 2^31 slots and jumps right after.
+    (0, 1 << 31),
+    (0, (1 << 31) + 1),
+    (1, u32::MAX - 1),
+    (u64::MAX, 1),
+    (0x8000_0000_0000_0000, u32::MAX),
+];
+
+#[test]
+fn test_regressions() {
+    for (key, slots) in CASES {
+        check(*key, *slots);
+    }
+}
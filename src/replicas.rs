@@ -0,0 +1,474 @@
+use crate::seeded::mix64;
+use crate::{hash, Slots};
+
+/// Fills `out` with distinct buckets of `key` in replica order and returns how many were written,
+/// which is `min(out.len(), slots)`. The first replica is always the [`hash`] bucket.
+///
+/// Replica `i` is jump hashed over the `slots - i` buckets not taken by the previous replicas,
+/// so nothing is allocated and every replica is uniformly distributed.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, hash_replicas_into};
+///
+/// let mut replicas = [0; 3];
+///
+/// assert_eq!(hash_replicas_into(123456, 1000.into(), &mut replicas), 3);
+/// assert_eq!(replicas[0], hash(123456, 1000))
+/// ```
+pub fn hash_replicas_into(key: u64, slots: Slots, out: &mut [u32]) -> usize {
+    let count = out.len().min(slots.get() as usize);
+    for i in 0..count {
+        let remaining = slots.get() - i as u32;
+        let index = hash(replica_key(key, i), remaining);
+        out[i] = nth_free(index, &out[..i]);
+    }
+    count
+}
+
+/// Fills `out` with distinct node indices of `key` picked with probability proportional to `weights`,
+/// in replica order, and returns how many were written, which is `min(out.len(), nodes with a positive weight)`.
+/// The first replica is always the [`weighted_hash`](crate::weighted_hash) node.
+///
+/// Replica `i` is jump hashed over the total weight of the nodes not taken by the previous replicas,
+/// so nothing is allocated.
+///
+/// Panics if the weights sum to zero or to more than `u32::MAX`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{weighted_hash, weighted_replicas_into};
+///
+/// let mut replicas = [0; 2];
+///
+/// assert_eq!(weighted_replicas_into(123456, &[1, 2, 3], &mut replicas), 2);
+/// assert_eq!(replicas[0], weighted_hash(123456, &[1, 2, 3]))
+/// ```
+pub fn weighted_replicas_into(key: u64, weights: &[u32], out: &mut [usize]) -> usize {
+    let total = weights.iter().map(|weight| *weight as u64).sum::<u64>();
+    let mut remaining = u32::try_from(total).expect("weights must sum to at most u32::MAX");
+    assert!(remaining > 0, "weights must sum to more than 0");
+
+    let count = out
+        .len()
+        .min(weights.iter().filter(|weight| **weight > 0).count());
+    for i in 0..count {
+        let bucket = hash(replica_key(key, i), remaining) as u64;
+        let mut end = 0u64;
+        let (node, weight) = weights
+            .iter()
+            .enumerate()
+            .filter(|(node, _)| !out[..i].contains(node))
+            .find(|(_, weight)| {
+                end += **weight as u64;
+                bucket < end
+            })
+            .expect("bucket is less than the remaining weight");
+        out[i] = node;
+        remaining -= weight;
+    }
+    count
+}
+
+/// Returns every bucket ordered by preference for `key`, a permutation of `0..slots` to try in turn on failure.
+/// The order is the replica order, so the first element is the [`hash`] bucket and the first `r` elements
+/// are the ones [`hash_replicas_into`] writes for `r` replicas.
+///
+/// # Memory
+///
+/// The whole permutation is materialized, the result and the free-bucket index both take O(slots) memory,
+/// about 32 GB at `u32::MAX` slots. With large slot counts take the first replicas with [`hash_replicas_into`],
+/// or the first available bucket with [`route_first_available`], instead.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{bucket_preference, hash};
+///
+/// let preference = bucket_preference(123456, 10.into());
+///
+/// assert_eq!(preference.len(), 10);
+/// assert_eq!(preference[0], hash(123456, 10))
+/// ```
+pub fn bucket_preference(key: u64, slots: Slots) -> Vec<u32> {
+    let mut free = FreeBuckets::new(slots.get());
+    (0..slots.get())
+        .map(|i| {
+            let index = hash(replica_key(key, i as usize), slots.get() - i);
+            free.take_nth(index)
+        })
+        .collect()
+}
+
+/// Returns the first bucket of `key` in [`bucket_preference`] order for which `is_up` is `true`,
+/// or `None` if every bucket is down. A key whose [`hash`] bucket is up is never moved.
+///
+/// Skipping `k` down buckets costs O(k²) time and O(k) memory. Returning `None` means every bucket
+/// was visited, which costs O(slots²) time and O(slots) memory, so with huge slot counts
+/// make sure some bucket is up.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{bucket_preference, hash, route_first_available};
+///
+/// let primary = hash(123456, 10);
+///
+/// assert_eq!(route_first_available(123456, 10.into(), |_| true), Some(primary));
+/// assert_eq!(
+///     route_first_available(123456, 10.into(), |bucket| bucket != primary),
+///     Some(bucket_preference(123456, 10.into())[1])
+/// );
+/// assert_eq!(route_first_available(123456, 10.into(), |_| false), None)
+/// ```
+pub fn route_first_available(key: u64, slots: Slots, is_up: impl Fn(u32) -> bool) -> Option<u32> {
+    let primary = hash(key, slots);
+    if is_up(primary) {
+        return Some(primary);
+    }
+
+    // The down buckets are kept sorted, so each pick is a binary search plus an O(k) insertion.
+    let mut taken = vec![primary];
+    for i in 1..slots.get() {
+        let index = hash(replica_key(key, i as usize), slots.get() - i);
+        let bucket = nth_free_sorted(index, &taken);
+        if is_up(bucket) {
+            return Some(bucket);
+        }
+        let position = taken.partition_point(|taken| *taken < bucket);
+        taken.insert(position, bucket);
+    }
+    None
+}
+
+/// Returns the first bucket of `key` in [`bucket_preference`] order that is not in `excluded`,
+/// or `None` if every bucket is excluded. Draining a bucket this way keeps the slot count,
+/// so only the keys of the excluded buckets move.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{bucket_preference, route_excluding};
+///
+/// let preference = bucket_preference(123456, 10.into());
+///
+/// assert_eq!(route_excluding(123456, 10.into(), &[preference[0]]), Some(preference[1]))
+/// ```
+pub fn route_excluding(key: u64, slots: Slots, excluded: &[u32]) -> Option<u32> {
+    route_first_available(key, slots, |bucket| !excluded.contains(&bucket))
+}
+
+/// Returns the replica of `key` the writer `writer_id` writes to, the `writer_id % r`-th of its `r` replicas
+/// in [`bucket_preference`] order, so independent writers spread their writes over the replicas predictably.
+/// The replica count is capped at `slots`.
+///
+/// Panics if `r` is zero.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{bucket_preference, route_replica_for_writer};
+///
+/// let preference = bucket_preference(123456, 10.into());
+///
+/// assert_eq!(route_replica_for_writer(123456, 10.into(), 3, 0), preference[0]);
+/// assert_eq!(route_replica_for_writer(123456, 10.into(), 3, 4), preference[1])
+/// ```
+pub fn route_replica_for_writer(key: u64, slots: Slots, r: usize, writer_id: u64) -> u32 {
+    assert!(r > 0, "replica count must be greater than 0");
+    let r = r.min(slots.get() as usize);
+    let mut replicas = vec![0; (writer_id % r as u64) as usize + 1];
+    hash_replicas_into(key, slots, &mut replicas);
+    replicas[replicas.len() - 1]
+}
+
+/// A Fenwick tree of the buckets not taken yet, finding the `n`-th free one in O(log slots).
+struct FreeBuckets {
+    tree: Vec<u32>,
+}
+
+impl FreeBuckets {
+    fn new(slots: u32) -> Self {
+        // Every bucket is free, so node `i` counts the `i & -i` buckets it covers.
+        let tree = (0..=slots).map(|i| i & i.wrapping_neg()).collect();
+        Self { tree }
+    }
+
+    fn take_nth(&mut self, n: u32) -> u32 {
+        let len = self.tree.len() - 1;
+        let (mut position, mut remaining) = (0, n + 1);
+        let mut step = 1 << (usize::BITS - 1 - len.leading_zeros());
+        while step > 0 {
+            if position + step <= len && self.tree[position + step] < remaining {
+                position += step;
+                remaining -= self.tree[position];
+            }
+            step >>= 1;
+        }
+
+        let mut node = position + 1;
+        while node <= len {
+            self.tree[node] -= 1;
+            node += node & node.wrapping_neg();
+        }
+        position as u32
+    }
+}
+
+fn replica_key(key: u64, replica: usize) -> u64 {
+    if replica == 0 {
+        key
+    } else {
+        mix64(key ^ (replica as u64).wrapping_mul(0x9e3779b97f4a7c15))
+    }
+}
+
+/// Returns the `index`-th smallest bucket that is not in `taken`.
+fn nth_free(index: u32, taken: &[u32]) -> u32 {
+    let mut bucket = index;
+    loop {
+        let skipped = taken.iter().filter(|taken| **taken <= bucket).count() as u32;
+        if index + skipped == bucket {
+            return bucket;
+        }
+        bucket = index + skipped;
+    }
+}
+
+/// Same as [`nth_free`] for sorted `taken`, in O(log taken).
+/// `taken[i] - i` is the number of free buckets below `taken[i]`, which never decreases,
+/// so the buckets taken before the answer are the ones with at most `index` free buckets below them.
+fn nth_free_sorted(index: u32, taken: &[u32]) -> u32 {
+    let (mut low, mut high) = (0, taken.len());
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if taken[middle] - middle as u32 <= index {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    index + low as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{nth_free, nth_free_sorted};
+    use crate::{
+        bucket_preference, hash, hash_replicas_into, route_excluding, route_first_available,
+        route_replica_for_writer, weighted_hash, weighted_replicas_into, Slots,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_undersized_buffer() {
+        let slots = Slots::from(10);
+        for key in 0..1000 {
+            let mut out = [u32::MAX; 3];
+            assert_eq!(hash_replicas_into(key, slots, &mut out), 3);
+            assert_eq!(out[0], hash(key, slots));
+            assert_eq!(out.iter().collect::<HashSet<_>>().len(), 3);
+            assert!(out.iter().all(|bucket| *bucket < 10));
+        }
+    }
+
+    #[test]
+    fn test_oversized_buffer() {
+        let slots = Slots::from(5);
+        for key in 0..1000 {
+            let mut out = [u32::MAX; 8];
+            assert_eq!(hash_replicas_into(key, slots, &mut out), 5);
+
+            let written: HashSet<_> = out[..5].iter().copied().collect();
+            assert_eq!(written, (0..5).collect());
+            assert!(out[5..].iter().all(|bucket| *bucket == u32::MAX));
+        }
+    }
+
+    #[test]
+    fn test_replicas_are_uniform() {
+        let slots = Slots::from(4);
+        let mut counts = [0u32; 4];
+        for key in 0..40_000 {
+            let mut out = [0; 2];
+            hash_replicas_into(key, slots, &mut out);
+            counts[out[1] as usize] += 1;
+        }
+        assert!(counts.iter().all(|count| count.abs_diff(10_000) < 500));
+    }
+
+    #[test]
+    fn test_bucket_preference() {
+        for slots in [1, 2, 3, 10, 100, 257] {
+            let slots = Slots::from(slots);
+            for key in 0..100 {
+                let preference = bucket_preference(key, slots);
+                assert_eq!(preference[0], hash(key, slots));
+
+                let mut sorted = preference.clone();
+                sorted.sort_unstable();
+                assert_eq!(sorted, (0..slots.get()).collect::<Vec<_>>());
+
+                let mut replicas = [0; 5];
+                let count = hash_replicas_into(key, slots, &mut replicas);
+                assert_eq!(preference[..count], replicas[..count]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_route_replica_for_writer() {
+        let slots = Slots::from(100);
+        for key in 0..100 {
+            let preference = bucket_preference(key, slots);
+            let mut picked: Vec<u32> = (0..3)
+                .map(|writer| route_replica_for_writer(key, slots, 3, writer))
+                .collect();
+            assert_eq!(picked, preference[..3]);
+            assert_eq!(route_replica_for_writer(key, slots, 3, 7), preference[1]);
+
+            picked.sort_unstable();
+            picked.dedup();
+            assert_eq!(picked.len(), 3);
+        }
+
+        for writer in 0..10 {
+            assert_eq!(
+                route_replica_for_writer(42, 2.into(), 5, writer),
+                bucket_preference(42, 2.into())[writer as usize % 2]
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "replica count must be greater than 0")]
+    fn test_route_replica_for_writer_zero_replicas() {
+        route_replica_for_writer(1, 10.into(), 0, 1);
+    }
+
+    #[test]
+    fn test_route_first_available() {
+        let slots = Slots::from(10);
+        for key in 0..1000 {
+            let preference = bucket_preference(key, slots);
+            assert_eq!(
+                route_first_available(key, slots, |_| true),
+                Some(preference[0])
+            );
+
+            // With the primary down the key falls to its next preference.
+            let primary = preference[0];
+            assert_eq!(
+                route_first_available(key, slots, |bucket| bucket != primary),
+                Some(preference[1])
+            );
+
+            let down = &preference[..5];
+            assert_eq!(
+                route_first_available(key, slots, |bucket| !down.contains(&bucket)),
+                Some(preference[5])
+            );
+            assert_eq!(route_first_available(key, slots, |_| false), None);
+        }
+        assert_eq!(route_first_available(42, Slots::from(1), |_| false), None);
+    }
+
+    #[test]
+    fn test_nth_free_sorted() {
+        let taken = [1, 2, 5, 9, 10, 11, 20];
+        for index in 0..30 {
+            assert_eq!(nth_free_sorted(index, &taken), nth_free(index, &taken));
+            assert!(!taken.contains(&nth_free_sorted(index, &taken)));
+        }
+        assert_eq!(nth_free_sorted(3, &[]), 3);
+    }
+
+    #[test]
+    fn test_route_first_available_max_slots() {
+        let slots = Slots::from(u32::MAX);
+        for key in 0..100 {
+            let mut replicas = [0; 3];
+            hash_replicas_into(key, slots, &mut replicas);
+
+            let primary = replicas[0];
+            assert_eq!(
+                route_first_available(key, slots, |bucket| bucket != primary),
+                Some(replicas[1])
+            );
+            let down = &replicas[..2];
+            assert_eq!(
+                route_first_available(key, slots, |bucket| !down.contains(&bucket)),
+                Some(replicas[2])
+            );
+        }
+    }
+
+    #[test]
+    fn test_route_excluding() {
+        let slots = Slots::from(8);
+        for key in 0..1000 {
+            let preference = bucket_preference(key, slots);
+            assert_eq!(route_excluding(key, slots, &[]), Some(hash(key, slots)));
+            assert_eq!(
+                route_excluding(key, slots, &[preference[0]]),
+                Some(preference[1])
+            );
+
+            // Draining a bucket the key doesn't live on leaves it in place.
+            assert_eq!(
+                route_excluding(key, slots, &[preference[7]]),
+                Some(preference[0])
+            );
+            assert_eq!(route_excluding(key, slots, &preference), None);
+        }
+    }
+
+    #[test]
+    fn test_route_excluding_max_slots() {
+        let slots = Slots::from(u32::MAX);
+        let drained = hash(0, slots);
+        for key in 0..1000 {
+            let mut replicas = [0; 2];
+            hash_replicas_into(key, slots, &mut replicas);
+
+            let expected = if replicas[0] == drained {
+                replicas[1]
+            } else {
+                replicas[0]
+            };
+            assert_eq!(route_excluding(key, slots, &[drained]), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_empty_buffer() {
+        assert_eq!(hash_replicas_into(1, Slots::from(5), &mut []), 0);
+    }
+
+    #[test]
+    fn test_weighted_replicas_into() {
+        let weights = [1, 0, 3, 6];
+        let mut counts = [0u32; 4];
+        for key in 0..10_000 {
+            let mut out = [usize::MAX; 2];
+            assert_eq!(weighted_replicas_into(key, &weights, &mut out), 2);
+            assert_eq!(out[0], weighted_hash(key, &weights));
+            assert_ne!(out[0], out[1]);
+            for node in out {
+                assert_ne!(node, 1);
+                counts[node] += 1;
+            }
+        }
+        assert!(counts[3] > counts[2] && counts[2] > counts[0]);
+
+        // Only the nodes with a positive weight can be written.
+        let mut out = [usize::MAX; 5];
+        assert_eq!(weighted_replicas_into(42, &weights, &mut out), 3);
+        let mut written = out[..3].to_vec();
+        written.sort_unstable();
+        assert_eq!(written, vec![0, 2, 3]);
+        assert!(out[3..].iter().all(|node| *node == usize::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "weights must sum to more than 0")]
+    fn test_weighted_replicas_zero_weights() {
+        weighted_replicas_into(1, &[0, 0], &mut [0; 2]);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::seeded::mix64;
+use crate::sharder::slots_for;
+use crate::{hash, Sharder, Slots};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// `Router` abstracts over sharding strategies, so they can be swapped at runtime for experiments.
+/// [`Sharder`] is the jump hashing implementation, [`ModuloRouter`] and [`RendezvousRouter`] are the classic alternatives.
+///
+/// Example:
+/// ```rust
+/// use jumpch::{ModuloRouter, RendezvousRouter, Router, Sharder};
+///
+/// let nodes = vec!["a", "b", "c"];
+/// let routers: Vec<Box<dyn Router<&str>>> = vec![
+///     Box::new(Sharder::new(nodes.clone())),
+///     Box::new(ModuloRouter::new(nodes.clone())),
+///     Box::new(RendezvousRouter::new(nodes.clone())),
+/// ];
+///
+/// for router in &routers {
+///     assert!(nodes.contains(router.route(123456)))
+/// }
+/// ```
+pub trait Router<T> {
+    /// Returns the node of `key`
+    fn route(&self, key: u64) -> &T;
+}
+
+impl<T> Router<T> for Sharder<T> {
+    fn route(&self, key: u64) -> &T {
+        Sharder::route(self, &key)
+    }
+}
+
+/// `ModuloRouter` routes `key` to node `key % nodes`.
+/// Almost every key moves when the number of nodes changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuloRouter<T> {
+    nodes: Vec<T>,
+}
+
+impl<T> ModuloRouter<T> {
+    /// Create new ModuloRouter over `nodes`.
+    ///
+    /// Panics if `nodes` is empty or has more than `u32::MAX` nodes.
+    pub fn new(nodes: Vec<T>) -> Self {
+        slots_for(&nodes);
+        Self { nodes }
+    }
+}
+
+impl<T> Router<T> for ModuloRouter<T> {
+    fn route(&self, key: u64) -> &T {
+        &self.nodes[(key % self.nodes.len() as u64) as usize]
+    }
+}
+
+/// `RendezvousRouter` routes `key` to the node with the highest score of `(node, key)`,
+/// also known as highest random weight hashing. It moves as few keys as jump hashing, but costs O(nodes) per key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RendezvousRouter<T> {
+    nodes: Vec<T>,
+}
+
+impl<T: Hash> RendezvousRouter<T> {
+    /// Create new RendezvousRouter over `nodes`.
+    ///
+    /// Panics if `nodes` is empty or has more than `u32::MAX` nodes.
+    pub fn new(nodes: Vec<T>) -> Self {
+        slots_for(&nodes);
+        Self { nodes }
+    }
+}
+
+impl<T: Hash> Router<T> for RendezvousRouter<T> {
+    fn route(&self, key: u64) -> &T {
+        self.nodes
+            .iter()
+            .max_by_key(|node| {
+                let mut hasher = DefaultHasher::new();
+                node.hash(&mut hasher);
+                mix64(hasher.finish() ^ key)
+            })
+            .expect("router has at least one node")
+    }
+}
+
+/// `NumRouter` routes numeric keys that are already well distributed straight to buckets with [`hash`],
+/// with no `Hash` or `Hasher` indirection.
+///
+/// Example:
+/// ```rust
+/// use jumpch::{hash, NumRouter};
+///
+/// let router = NumRouter::new(1000);
+///
+/// assert_eq!(router.route(123456), hash(123456, 1000))
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NumRouter {
+    slots: Slots,
+}
+
+impl NumRouter {
+    /// Create new NumRouter over `slots` buckets
+    pub fn new<S: Into<Slots>>(slots: S) -> Self {
+        Self {
+            slots: slots.into(),
+        }
+    }
+
+    /// Returns the number of buckets
+    pub fn slots(&self) -> Slots {
+        self.slots
+    }
+
+    /// Returns the bucket of `key`
+    #[inline]
+    pub fn route(&self, key: u64) -> u32 {
+        hash(key, self.slots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, ModuloRouter, NumRouter, RendezvousRouter, Router, Sharder, Slots};
+
+    fn moved<R: Router<u32>>(before: &R, after: &R) -> usize {
+        (0..10_000)
+            .filter(|key| before.route(*key) != after.route(*key))
+            .count()
+    }
+
+    #[test]
+    fn test_routes_to_nodes() {
+        let nodes: Vec<u32> = (0..5).collect();
+        let routers: Vec<Box<dyn Router<u32>>> = vec![
+            Box::new(Sharder::new(nodes.clone())),
+            Box::new(ModuloRouter::new(nodes.clone())),
+            Box::new(RendezvousRouter::new(nodes.clone())),
+        ];
+        for router in &routers {
+            for key in 0..1000 {
+                assert!(nodes.contains(router.route(key)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_jump_moves_fewest() {
+        let (before, after): (Vec<u32>, Vec<u32>) = ((0..10).collect(), (0..11).collect());
+
+        let jump = moved(&Sharder::new(before.clone()), &Sharder::new(after.clone()));
+        let modulo = moved(
+            &ModuloRouter::new(before.clone()),
+            &ModuloRouter::new(after.clone()),
+        );
+        let rendezvous = moved(
+            &RendezvousRouter::new(before),
+            &RendezvousRouter::new(after),
+        );
+
+        // The minimal movement is 1/11 of the keys, rendezvous hashing gets there too.
+        assert!(jump.abs_diff(10_000 / 11) < 150, "{jump}");
+        assert!(rendezvous.abs_diff(10_000 / 11) < 150, "{rendezvous}");
+        assert!(modulo > 5 * jump, "{modulo} vs {jump}");
+    }
+
+    #[test]
+    fn test_num_router() {
+        for slots in [1, 10, 1000, u32::MAX] {
+            let router = NumRouter::new(slots);
+            assert_eq!(router.slots(), Slots::from(slots));
+            for key in (0..1000).chain([u64::MAX]) {
+                assert_eq!(router.route(key), hash(key, slots));
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use crate::Slots;
+
+/// Returns the smallest slot count that keeps the mean load of `total_keys` at or under `target_per_bucket`.
+/// The result is at least one slot and saturates at `u32::MAX`.
+///
+/// Panics if `target_per_bucket` is zero.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::slots_for_target_load;
+///
+/// assert_eq!(slots_for_target_load(1000, 100).get(), 10);
+/// assert_eq!(slots_for_target_load(1001, 100).get(), 11);
+/// assert_eq!(slots_for_target_load(0, 100).get(), 1);
+/// ```
+pub fn slots_for_target_load(total_keys: u64, target_per_bucket: u64) -> Slots {
+    assert!(
+        target_per_bucket > 0,
+        "target_per_bucket must be greater than 0"
+    );
+    // Rounds the quotient up without `total_keys + target_per_bucket - 1`, which could overflow.
+    let (quotient, remainder) = (
+        total_keys / target_per_bucket,
+        total_keys % target_per_bucket,
+    );
+    let slots = (quotient + (remainder > 0) as u64).clamp(1, u32::MAX as u64);
+    Slots::from(slots as u32)
+}
+
+/// Decides whether the buckets described by `loads` should be scaled up.
+/// Returns `Some(recommended_slots)` when the most loaded bucket exceeds `target_per_bucket`, else `None`.
+/// The recommendation comes from [`slots_for_target_load`] and is always greater than `loads.len()`.
+///
+/// Panics if `target_per_bucket` is zero.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::needs_scale_up;
+///
+/// assert_eq!(needs_scale_up(&[90, 110, 100], 100).map(|s| s.get()), Some(4));
+/// assert_eq!(needs_scale_up(&[90, 100, 100], 100), None);
+/// ```
+pub fn needs_scale_up(loads: &[u64], target_per_bucket: u64) -> Option<Slots> {
+    let current = Slots::try_from(loads.len()).ok()?;
+    rebalance_target(current, loads, target_per_bucket)
+}
+
+/// Recommends the slot count to resize `current` to, given the per-bucket `loads` it produced.
+/// Returns `None` if no bucket exceeds `target_per_bucket`, otherwise the count from [`slots_for_target_load`],
+/// at least one more than `current` so that a single hot bucket still triggers growth.
+///
+/// Panics if `target_per_bucket` is zero.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::rebalance_target;
+///
+/// assert_eq!(rebalance_target(3.into(), &[300, 310, 290], 100).map(|s| s.get()), Some(9));
+/// assert_eq!(rebalance_target(3.into(), &[30, 31, 29], 100), None);
+/// ```
+pub fn rebalance_target(current: Slots, loads: &[u64], target_per_bucket: u64) -> Option<Slots> {
+    assert!(
+        target_per_bucket > 0,
+        "target_per_bucket must be greater than 0"
+    );
+    let max = loads.iter().copied().max()?;
+    if max <= target_per_bucket {
+        return None;
+    }
+
+    // Saturate instead of overflowing: past `u64::MAX` keys the recommendation is capped at `u32::MAX` slots anyway.
+    let total = loads
+        .iter()
+        .fold(0u64, |total, load| total.saturating_add(*load));
+    let recommended = slots_for_target_load(total, target_per_bucket);
+    Some(recommended.max(current.saturating_add(1)))
+}
+
+/// Returns the slot counts to pass through when growing from `from` to `to` by `step` slots at a time,
+/// both ends included. The last step is capped at `to`, so it may be shorter.
+/// Every step moves about `step / new_slots` of the keys, so small steps keep each reshard cheap.
+///
+/// Panics if `step` is zero or `to` is less than `from`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{growth_schedule, Slots};
+///
+/// let schedule: Vec<u32> = growth_schedule(8.into(), 18.into(), 4).into_iter().map(Slots::get).collect();
+///
+/// assert_eq!(schedule, vec![8, 12, 16, 18])
+/// ```
+pub fn growth_schedule(from: Slots, to: Slots, step: u32) -> Vec<Slots> {
+    assert!(step > 0, "step must be greater than 0");
+    assert!(
+        from <= to,
+        "growth schedule must grow, got {} slots shrinking to {}",
+        from.get(),
+        to.get()
+    );
+
+    let mut schedule = vec![from];
+    let mut current = from;
+    while current < to {
+        current = current.saturating_add(step).min(to);
+        schedule.push(current);
+    }
+    schedule
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{growth_schedule, needs_scale_up, rebalance_target, slots_for_target_load, Slots};
+
+    #[test]
+    fn test_slots_for_target_load() {
+        assert_eq!(slots_for_target_load(0, 1), Slots::from(1));
+        assert_eq!(slots_for_target_load(10, 3), Slots::from(4));
+        assert_eq!(slots_for_target_load(u64::MAX, 1), Slots::from(u32::MAX));
+        assert_eq!(slots_for_target_load(u64::MAX, u64::MAX), Slots::from(1));
+        assert_eq!(
+            slots_for_target_load(u64::MAX - 1, u64::MAX),
+            Slots::from(1)
+        );
+        assert_eq!(
+            slots_for_target_load(u64::MAX, 1 << 40),
+            Slots::from(1 << 24)
+        );
+    }
+
+    #[test]
+    fn test_needs_scale_up() {
+        let loads = [250, 300, 280, 270];
+        let recommended = needs_scale_up(&loads, 200).unwrap();
+        assert!(recommended > Slots::from(loads.len() as u32));
+        assert_eq!(recommended, Slots::from(6));
+
+        // A single hot bucket still asks for at least one more slot.
+        assert_eq!(needs_scale_up(&[10, 10, 201], 200), Some(Slots::from(4)));
+    }
+
+    #[test]
+    fn test_no_scale_up() {
+        assert_eq!(needs_scale_up(&[150, 200, 120], 200), None);
+        assert_eq!(needs_scale_up(&[], 200), None);
+    }
+
+    #[test]
+    fn test_rebalance_target_under_loaded() {
+        let current = Slots::from(4);
+        assert_eq!(rebalance_target(current, &[50, 60, 40, 55], 100), None);
+        assert_eq!(rebalance_target(current, &[100, 100, 100, 100], 100), None);
+    }
+
+    #[test]
+    fn test_rebalance_target_over_loaded() {
+        let current = Slots::from(4);
+        let recommended = rebalance_target(current, &[250, 260, 240, 250], 100).unwrap();
+        assert!(recommended > current);
+        assert_eq!(recommended, Slots::from(10));
+        assert_eq!(
+            rebalance_target(Slots::from(u32::MAX), &[101], 100),
+            Some(Slots::from(u32::MAX))
+        );
+        assert_eq!(
+            rebalance_target(current, &[u64::MAX, u64::MAX], 1),
+            Some(Slots::from(u32::MAX))
+        );
+    }
+
+    #[test]
+    fn test_growth_schedule() {
+        let schedule = growth_schedule(Slots::from(8), Slots::from(20), 4);
+        assert_eq!(schedule, [8, 12, 16, 20].map(Slots::from));
+
+        assert_eq!(
+            growth_schedule(Slots::from(8), Slots::from(8), 4),
+            vec![Slots::from(8)]
+        );
+        assert_eq!(
+            growth_schedule(Slots::from(u32::MAX - 1), Slots::from(u32::MAX), u32::MAX),
+            [u32::MAX - 1, u32::MAX].map(Slots::from)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "growth schedule must grow, got 20 slots shrinking to 8")]
+    fn test_growth_schedule_shrinking() {
+        growth_schedule(Slots::from(20), Slots::from(8), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "step must be greater than 0")]
+    fn test_growth_schedule_zero_step() {
+        growth_schedule(Slots::from(8), Slots::from(20), 0);
+    }
+}
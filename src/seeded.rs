@@ -0,0 +1,172 @@
+use crate::fnv::fnv1a;
+use crate::{hash, Slots};
+
+/// Routes `key` in the independent namespace selected by `seed`.
+/// The key is mixed with the seed before jump hashing, so different seeds decorrelate assignments,
+/// while growing the slot count stays consistent for every seed.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::hash_seeded;
+///
+/// assert_eq!(hash_seeded(123456, 1, 1000), hash_seeded(123456, 1, 1000))
+/// ```
+pub fn hash_seeded<S: Into<Slots>>(key: u64, seed: u64, slots: S) -> u32 {
+    hash(mix64(key ^ mix64(seed)), slots)
+}
+
+/// Routes `key` in the namespace named `domain`, see [`hash_seeded`].
+/// The seed is the 64-bit FNV-1a hash of the domain, so it never changes between versions.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::hash_domain;
+///
+/// assert!(hash_domain("sessions", 123456, 1000.into()) < 1000)
+/// ```
+pub fn hash_domain(domain: &str, key: u64, slots: Slots) -> u32 {
+    hash_seeded(key, fnv1a(domain.as_bytes()), slots)
+}
+
+/// Routes `key` in the time window numbered `window`, e.g. `epoch_seconds / interval`, see [`hash_seeded`].
+/// A key stays on one bucket within a window and is reshuffled independently in the next one,
+/// rotating load across buckets as cached entries expire.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::route_windowed;
+///
+/// let window = 1_700_000_000 / 3600;
+/// assert_eq!(route_windowed(123456, 1000.into(), window), route_windowed(123456, 1000.into(), window))
+/// ```
+pub fn route_windowed(key: u64, slots: Slots, window: u64) -> u32 {
+    hash_seeded(key, window, slots)
+}
+
+/// Routes `key` like [`hash`], then shuffles the bucket index with a permutation chosen by `permutation_seed`.
+/// The permutation is a bijection of `0..slots`, so the distribution stays uniform while services
+/// using different seeds place the same key on different physical buckets.
+///
+/// Growing the slot count changes the permutation too, so unlike [`hash`] most keys move on resize.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::hash_permuted;
+///
+/// assert!(hash_permuted(123456, 1000.into(), 1) < 1000)
+/// ```
+pub fn hash_permuted(key: u64, slots: Slots, permutation_seed: u64) -> u32 {
+    let n = slots.get() as u64;
+    let (multiplier, offset) = permutation(n, permutation_seed);
+    let bucket = hash(key, slots) as u64;
+    ((bucket as u128 * multiplier as u128 + offset as u128) % n as u128) as u32
+}
+
+/// An affine permutation `x * multiplier + offset (mod n)`, a bijection while `multiplier` is coprime to `n`.
+fn permutation(n: u64, seed: u64) -> (u64, u64) {
+    let offset = mix64(seed) % n;
+    let mut multiplier = mix64(seed ^ 0x9e3779b97f4a7c15) % n;
+    while gcd(multiplier, n) != 1 {
+        multiplier = (multiplier + 1) % n;
+    }
+    (multiplier, offset)
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The splitmix64 finalizer, spreading every bit of `x` over the whole output.
+pub(crate) fn mix64(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{distribution, hash_domain, hash_permuted, hash_seeded, route_windowed, Slots};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_hash_seeded() {
+        let slots = Slots::from(100);
+        let differ = (0..1000)
+            .filter(|key| hash_seeded(*key, 1, slots) != hash_seeded(*key, 2, slots))
+            .count();
+        assert!(differ > 900);
+
+        for key in 0..1000 {
+            let (old, new) = (hash_seeded(key, 7, 10), hash_seeded(key, 7, 11));
+            assert!(old == new || new == 10);
+        }
+    }
+
+    #[test]
+    fn test_hash_domain() {
+        let slots = Slots::from(100);
+        let differ = (0..1000)
+            .filter(|key| hash_domain("users", *key, slots) != hash_domain("orders", *key, slots))
+            .count();
+        assert!(differ > 900);
+
+        for key in 0..100 {
+            assert_eq!(
+                hash_domain("users", key, slots),
+                hash_domain("users", key, slots)
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_permutation() {
+        for slots in [1, 2, 3, 10, 64, 97, 1000] {
+            for seed in 0..10 {
+                let (multiplier, offset) = super::permutation(slots, seed);
+                let image: HashSet<_> = (0..slots)
+                    .map(|x| (x * multiplier + offset) % slots)
+                    .collect();
+                assert_eq!(image.len(), slots as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn test_uniformity_preserved() {
+        let slots = Slots::from(10);
+        let mut counts = vec![0u64; 10];
+        for key in 0..100_000 {
+            counts[hash_permuted(key, slots, 7) as usize] += 1;
+        }
+
+        let mut plain = distribution(0..100_000, slots);
+        plain.sort_unstable();
+        counts.sort_unstable();
+        assert_eq!(counts, plain);
+    }
+
+    #[test]
+    fn test_seeds_differ() {
+        let slots = Slots::from(100);
+        let differ = (0..1000)
+            .filter(|key| hash_permuted(*key, slots, 0) != hash_permuted(*key, slots, 1))
+            .count();
+        assert!(differ > 900);
+    }
+
+    #[test]
+    fn test_route_windowed() {
+        let slots = Slots::from(100);
+        for key in 0..1000 {
+            assert_eq!(route_windowed(key, slots, 5), route_windowed(key, slots, 5));
+        }
+
+        let rotated = (0..1000)
+            .filter(|key| route_windowed(*key, slots, 5) != route_windowed(*key, slots, 6))
+            .count();
+        assert!(rotated > 900);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{Sharder, Slots, SlotsError};
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Slots are serialized as a plain integer, zero is rejected on load.
+impl Serialize for Slots {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.get())
+    }
+}
+
+impl<'de> Deserialize<'de> for Slots {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let slots = u32::deserialize(deserializer)?;
+        Slots::new(slots).ok_or_else(|| D::Error::custom(SlotsError::ZeroSlots))
+    }
+}
+
+#[derive(Serialize)]
+struct SharderConfigRef<'a, T> {
+    slots: Slots,
+    nodes: &'a [T],
+}
+
+#[derive(Deserialize)]
+struct SharderConfig<T> {
+    slots: Slots,
+    nodes: Vec<T>,
+}
+
+/// A Sharder is serialized as its slot count and node list,
+/// loading fails if the slot count doesn't match the number of nodes.
+impl<T: Serialize> Serialize for Sharder<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SharderConfigRef {
+            slots: self.slots(),
+            nodes: self.nodes(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Sharder<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = SharderConfig::<T>::deserialize(deserializer)?;
+        if config.nodes.len() != config.slots.get() as usize {
+            return Err(D::Error::custom(format!(
+                "sharder has {} slots but {} nodes",
+                config.slots.get(),
+                config.nodes.len()
+            )));
+        }
+        Ok(Sharder::new(config.nodes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Sharder, Slots};
+
+    #[test]
+    fn test_slots() {
+        assert_eq!(serde_json::to_string(&Slots::from(42)).unwrap(), "42");
+        assert_eq!(
+            serde_json::from_str::<Slots>("42").unwrap(),
+            Slots::from(42)
+        );
+        assert!(serde_json::from_str::<Slots>("0").is_err());
+        assert!(serde_json::from_str::<Slots>("-1").is_err());
+    }
+
+    #[test]
+    fn test_sharder_round_trip() {
+        let sharder = Sharder::new(vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+        let json = serde_json::to_string(&sharder).unwrap();
+        assert_eq!(json, r#"{"slots":3,"nodes":["a","b","c"]}"#);
+
+        let loaded: Sharder<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, sharder);
+        for key in 0..1000u64 {
+            assert_eq!(loaded.route(&key), sharder.route(&key));
+        }
+    }
+
+    #[test]
+    fn test_sharder_mismatch() {
+        let json = r#"{"slots":2,"nodes":["a","b","c"]}"#;
+        let error = serde_json::from_str::<Sharder<String>>(json).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("sharder has 2 slots but 3 nodes"));
+    }
+}
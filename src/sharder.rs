@@ -0,0 +1,430 @@
+use crate::{hash_seeded, JumpHasher, Slots};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// `Sharder` routes keys to a list of nodes, using one bucket per node.
+/// Keys are hashed with `DefaultHasher` before jump hashing, so any `Hash` type can be routed.
+///
+/// Example:
+/// ```rust
+/// use jumpch::Sharder;
+///
+/// let sharder = Sharder::new(vec!["a", "b", "c"]);
+///
+/// assert!(["a", "b", "c"].contains(sharder.route(&"key")))
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sharder<T> {
+    nodes: Vec<T>,
+}
+
+impl<T> Sharder<T> {
+    /// Create new Sharder over `nodes`.
+    ///
+    /// Panics if `nodes` is empty or has more than `u32::MAX` nodes.
+    pub fn new(nodes: Vec<T>) -> Self {
+        slots_for(&nodes);
+        Self { nodes }
+    }
+
+    /// Create new Sharder over `nodes` like [`Sharder::new`], rejecting duplicate nodes which would waste buckets.
+    ///
+    /// Panics if `nodes` is empty or has more than `u32::MAX` nodes.
+    /// ```rust
+    /// use jumpch::Sharder;
+    ///
+    /// assert!(Sharder::new_unique(vec!["a", "b", "c"]).is_ok());
+    /// assert_eq!(Sharder::new_unique(vec!["a", "b", "a"]).unwrap_err().index(), 2);
+    /// ```
+    pub fn new_unique(nodes: Vec<T>) -> Result<Self, DuplicateNodeError>
+    where
+        T: Eq + Hash,
+    {
+        let mut seen = HashMap::with_capacity(nodes.len());
+        for (index, node) in nodes.iter().enumerate() {
+            if let Some(first) = seen.insert(node, index) {
+                return Err(DuplicateNodeError { first, index });
+            }
+        }
+        Ok(Self::new(nodes))
+    }
+
+    /// Returns the nodes in bucket order
+    pub fn nodes(&self) -> &[T] {
+        &self.nodes
+    }
+
+    /// Returns the number of buckets, which is the number of nodes
+    pub fn slots(&self) -> Slots {
+        slots_for(&self.nodes)
+    }
+
+    /// Returns the node index of `key`
+    pub fn index<K: Hash + ?Sized>(&self, key: &K) -> usize {
+        index_in(key, self.slots())
+    }
+
+    /// Returns the node of `key`
+    pub fn route<K: Hash + ?Sized>(&self, key: &K) -> &T {
+        &self.nodes[self.index(key)]
+    }
+
+    /// Returns the node serving `bucket`, or `None` if the bucket is out of range
+    /// ```rust
+    /// use jumpch::Sharder;
+    ///
+    /// let sharder = Sharder::new(vec!["a", "b", "c"]);
+    ///
+    /// assert_eq!(sharder.node_for_bucket(1), Some(&"b"));
+    /// assert_eq!(sharder.node_for_bucket(3), None);
+    /// ```
+    pub fn node_for_bucket(&self, bucket: u32) -> Option<&T> {
+        self.nodes.get(usize::try_from(bucket).ok()?)
+    }
+
+    /// Returns the bucket served by the node at `index` of [`Sharder::nodes`], or `None` if the index is out of range.
+    /// Nodes are stored in bucket order, so the bucket is the index itself.
+    /// ```rust
+    /// use jumpch::Sharder;
+    ///
+    /// let sharder = Sharder::new(vec!["a", "b", "c"]);
+    ///
+    /// assert_eq!(sharder.bucket_for_node(2), Some(2));
+    /// assert_eq!(sharder.bucket_for_node(3), None);
+    /// ```
+    pub fn bucket_for_node(&self, index: usize) -> Option<u32> {
+        (index < self.nodes.len()).then_some(index as u32)
+    }
+
+    /// Iterates the `(bucket, node)` pairs of the routing table in bucket order
+    /// ```rust
+    /// use jumpch::Sharder;
+    ///
+    /// let sharder = Sharder::new(vec!["a", "b", "c"]);
+    ///
+    /// assert_eq!(sharder.iter().collect::<Vec<_>>(), [(0, &"a"), (1, &"b"), (2, &"c")])
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &T)> {
+        (0..).zip(self.nodes.iter())
+    }
+
+    /// Lazily routes every key of `keys`, yielding their nodes in order
+    /// ```rust
+    /// use jumpch::Sharder;
+    ///
+    /// let sharder = Sharder::new(vec!["a", "b", "c"]);
+    ///
+    /// assert_eq!(sharder.route_iter(0..100u64).count(), 100)
+    /// ```
+    pub fn route_iter<'a, I, K>(&'a self, keys: I) -> impl Iterator<Item = &'a T> + 'a
+    where
+        I: IntoIterator<Item = K>,
+        I::IntoIter: 'a,
+        K: Hash,
+    {
+        let slots = self.slots();
+        keys.into_iter()
+            .map(move |key| &self.nodes[index_in(&key, slots)])
+    }
+
+    /// Simulates the failure of the node at index `failed`: returns every key of `keys` routed to it
+    /// as `(key, new_index)`, where `new_index` is the surviving node it would be routed to instead.
+    /// The keys are jump hashed again, seeded with `failed`, over the surviving nodes, so they spread evenly
+    /// over the survivors and keys of the other nodes never move.
+    ///
+    /// Panics if `failed` is out of range or the sharder has a single node.
+    /// ```rust
+    /// use jumpch::Sharder;
+    ///
+    /// let sharder = Sharder::new(vec!["a", "b", "c"]);
+    ///
+    /// let rerouted = sharder.reroute_on_failure(1, &[1, 2, 3, 4, 5]);
+    ///
+    /// assert!(rerouted.iter().all(|(key, new)| sharder.index(key) == 1 && *new != 1))
+    /// ```
+    pub fn reroute_on_failure(&self, failed: usize, keys: &[u64]) -> Vec<(u64, usize)> {
+        assert!(
+            failed < self.nodes.len(),
+            "node {failed} is out of range for {} nodes",
+            self.nodes.len()
+        );
+        let survivors = Slots::new(self.slots().get() - 1).expect("can't fail the only node");
+        let slots = self.slots();
+        keys.iter()
+            .filter(|key| index_in(*key, slots) == failed)
+            .map(|key| {
+                let mut hasher = DefaultHasher::new();
+                key.hash(&mut hasher);
+                let index = hash_seeded(hasher.finish(), failed as u64, survivors) as usize;
+                (*key, if index < failed { index } else { index + 1 })
+            })
+            .collect()
+    }
+
+    /// Replaces the nodes with `new_nodes` and reports every key of `keys` whose node index changed
+    /// as `(key, old_index, new_index)`.
+    ///
+    /// Panics if `new_nodes` is empty or has more than `u32::MAX` nodes.
+    /// ```rust
+    /// use jumpch::Sharder;
+    ///
+    /// let mut sharder = Sharder::new(vec!["a", "b", "c"]);
+    ///
+    /// let moved = sharder.resize(vec!["a", "b", "c", "d"], &[1, 2, 3, 4, 5]);
+    ///
+    /// assert!(moved.iter().all(|(_, _, new)| *new == 3))
+    /// ```
+    pub fn resize(&mut self, new_nodes: Vec<T>, keys: &[u64]) -> Vec<(u64, usize, usize)> {
+        let (from, to) = (self.slots(), slots_for(&new_nodes));
+        self.nodes = new_nodes;
+        keys.iter()
+            .map(|key| (*key, index_in(key, from), index_in(key, to)))
+            .filter(|(_, old, new)| old != new)
+            .collect()
+    }
+
+    /// Returns the complete migration ledger of moving from the current nodes to `new_nodes`:
+    /// `(key, old_index, new_index)` for every key of `keys`, unchanged keys included, in key order.
+    /// Unlike [`Sharder::resize`] the sharder itself is left untouched.
+    ///
+    /// Panics if `new_nodes` is empty or has more than `u32::MAX` nodes.
+    /// ```rust
+    /// use jumpch::Sharder;
+    ///
+    /// let sharder = Sharder::new(vec!["a", "b", "c"]);
+    ///
+    /// let ledger = sharder.full_reassignment(&["a", "b", "c", "d"], &[1, 2, 3, 4, 5]);
+    ///
+    /// assert_eq!(ledger.len(), 5)
+    /// ```
+    pub fn full_reassignment(&self, new_nodes: &[T], keys: &[u64]) -> Vec<(u64, usize, usize)> {
+        let (from, to) = (self.slots(), slots_for(new_nodes));
+        keys.iter()
+            .map(|key| (*key, index_in(key, from), index_in(key, to)))
+            .collect()
+    }
+}
+
+impl<T> FromIterator<T> for Sharder<T> {
+    /// Collects the nodes into a Sharder, see [`Sharder::new`].
+    /// ```rust
+    /// use jumpch::Sharder;
+    ///
+    /// let sharder: Sharder<_> = ["a", "b", "c"].into_iter().collect();
+    ///
+    /// assert_eq!(sharder.slots().get(), 3)
+    /// ```
+    fn from_iter<I: IntoIterator<Item = T>>(nodes: I) -> Self {
+        Self::new(nodes.into_iter().collect())
+    }
+}
+
+/// The error of [`Sharder::new_unique`], a node is listed twice.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DuplicateNodeError {
+    first: usize,
+    index: usize,
+}
+
+impl DuplicateNodeError {
+    /// Returns the index of the first occurrence of the duplicated node
+    pub fn first(&self) -> usize {
+        self.first
+    }
+
+    /// Returns the index of the duplicate
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Display for DuplicateNodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "node {} duplicates node {}", self.index, self.first)
+    }
+}
+
+impl Error for DuplicateNodeError {}
+
+pub(crate) fn slots_for<T>(nodes: &[T]) -> Slots {
+    assert!(!nodes.is_empty(), "sharder must have at least one node");
+    Slots::from(u32::try_from(nodes.len()).expect("sharder must have at most u32::MAX nodes"))
+}
+
+pub(crate) fn index_in<K: Hash + ?Sized>(key: &K, slots: Slots) -> usize {
+    let mut hasher: JumpHasher<DefaultHasher> = JumpHasher::new(slots.get());
+    key.hash(&mut hasher);
+    hasher.finish() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sharder;
+    use std::error::Error;
+
+    #[test]
+    fn test_route() {
+        let sharder = Sharder::new(vec!["a", "b", "c"]);
+        for key in 0..1000u64 {
+            assert_eq!(sharder.route(&key), &sharder.nodes()[sharder.index(&key)]);
+        }
+    }
+
+    #[test]
+    fn test_bucket_node_mapping() {
+        let sharder = Sharder::new(vec!["a", "b", "c", "d"]);
+        for (index, node) in sharder.nodes().iter().enumerate() {
+            let bucket = sharder.bucket_for_node(index).unwrap();
+            assert_eq!(sharder.node_for_bucket(bucket), Some(node));
+        }
+        assert_eq!(sharder.node_for_bucket(4), None);
+        assert_eq!(sharder.node_for_bucket(u32::MAX), None);
+        assert_eq!(sharder.bucket_for_node(4), None);
+
+        for key in 0..100u64 {
+            let bucket = sharder.index(&key) as u32;
+            assert_eq!(sharder.node_for_bucket(bucket), Some(sharder.route(&key)));
+        }
+    }
+
+    #[test]
+    fn test_iter() {
+        let nodes = vec!["a", "b", "c", "d"];
+        let sharder = Sharder::new(nodes.clone());
+
+        let pairs: Vec<(u32, &&str)> = sharder.iter().collect();
+
+        assert_eq!(pairs.len(), nodes.len());
+        for (expected, (bucket, node)) in (0..).zip(pairs) {
+            assert_eq!(bucket, expected);
+            assert!(std::ptr::eq(node, &sharder.nodes()[bucket as usize]));
+            assert_eq!(sharder.node_for_bucket(bucket), Some(node));
+        }
+    }
+
+    #[test]
+    fn test_route_iter() {
+        let sharder = Sharder::new(vec!["a", "b", "c", "d"]);
+        let keys: Vec<String> = (0..1000).map(|key| key.to_string()).collect();
+
+        let routed: Vec<&&str> = sharder.route_iter(keys.iter()).collect();
+
+        assert_eq!(routed.len(), keys.len());
+        for (key, node) in keys.iter().zip(routed) {
+            assert!(std::ptr::eq(node, sharder.route(key)));
+        }
+    }
+
+    #[test]
+    fn test_resize() {
+        let keys: Vec<u64> = (0..1000).collect();
+        let mut sharder = Sharder::new(vec!["a", "b", "c"]);
+        let before: Vec<_> = keys.iter().map(|key| sharder.index(key)).collect();
+
+        let moved = sharder.resize(vec!["a", "b", "c", "d"], &keys);
+
+        assert!(!moved.is_empty());
+        for (key, old) in keys.iter().zip(before) {
+            let new = sharder.index(key);
+            if old == new {
+                assert!(moved.iter().all(|(moved, _, _)| moved != key));
+            } else {
+                assert_eq!(new, 3);
+                assert!(moved.contains(&(*key, old, new)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_full_reassignment() {
+        let keys: Vec<u64> = (0..1000).collect();
+        let sharder = Sharder::new(vec!["a", "b", "c"]);
+        let new_nodes = ["a", "b", "c", "d"];
+
+        let ledger = sharder.full_reassignment(&new_nodes, &keys);
+
+        assert_eq!(ledger.len(), keys.len());
+        let grown = Sharder::new(new_nodes.to_vec());
+        for ((key, old, new), expected) in ledger.iter().zip(&keys) {
+            assert_eq!(key, expected);
+            assert_eq!(*old, sharder.index(key));
+            assert_eq!(*new, grown.index(key));
+            assert!(old == new || *new == 3);
+        }
+        assert!(ledger.iter().any(|(_, old, new)| old == new));
+
+        let mut resized = sharder.clone();
+        let moved = resized.resize(new_nodes.to_vec(), &keys);
+        let changed: Vec<_> = ledger
+            .into_iter()
+            .filter(|(_, old, new)| old != new)
+            .collect();
+        assert_eq!(changed, moved);
+    }
+
+    #[test]
+    fn test_reroute_on_failure() {
+        let keys: Vec<u64> = (0..10_000).collect();
+        let sharder = Sharder::new(vec!["a", "b", "c", "d", "e"]);
+        for failed in 0..5 {
+            let rerouted = sharder.reroute_on_failure(failed, &keys);
+            let on_failed: Vec<u64> = keys
+                .iter()
+                .copied()
+                .filter(|key| sharder.index(key) == failed)
+                .collect();
+            assert_eq!(
+                rerouted.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+                on_failed
+            );
+
+            // The failed keys spread evenly over the four survivors.
+            let mut survivors = [0usize; 5];
+            for (_, new) in &rerouted {
+                assert_ne!(*new, failed);
+                survivors[*new] += 1;
+            }
+            let expected = rerouted.len() / 4;
+            for (node, count) in survivors.iter().enumerate() {
+                if node != failed {
+                    assert!(count.abs_diff(expected) < expected / 5, "{survivors:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "can't fail the only node")]
+    fn test_reroute_only_node() {
+        Sharder::new(vec!["a"]).reroute_on_failure(0, &[1]);
+    }
+
+    #[test]
+    fn test_new_unique() {
+        let sharder = Sharder::new_unique(vec!["a", "b", "c"]).unwrap();
+        assert_eq!(sharder, Sharder::new(vec!["a", "b", "c"]));
+
+        let error = Sharder::new_unique(vec!["a", "b", "c", "b", "a"]).unwrap_err();
+        assert_eq!((error.first(), error.index()), (1, 3));
+        let error: Box<dyn Error> = Box::new(error);
+        assert_eq!(error.to_string(), "node 3 duplicates node 1");
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let nodes = vec!["a", "b", "c", "d"];
+        let sharder: Sharder<_> = nodes.clone().into_iter().collect();
+
+        assert_eq!(sharder.slots().get(), 4);
+        assert!(nodes.contains(sharder.route(&"key")));
+    }
+
+    #[test]
+    #[should_panic(expected = "sharder must have at least one node")]
+    fn test_empty() {
+        Sharder::<&str>::new(vec![]);
+    }
+}
@@ -0,0 +1,169 @@
+use std::fmt;
+use std::hash::Hasher;
+
+/// `SipHasher13` is SipHash-1-3 with a caller chosen 128-bit secret key, the algorithm behind `DefaultHasher`.
+/// Keys routed with a secret nobody else knows can't be crafted to overload a single bucket,
+/// while the routing stays deterministic for everyone sharing the secret.
+/// Integers are written little-endian, so the result is the same on every platform.
+///
+/// Example:
+/// ```rust
+/// use std::hash::Hasher;
+/// use jumpch::SipHasher13;
+///
+/// let mut hasher = SipHasher13::new_with_key([7; 16]);
+/// hasher.write(b"key");
+///
+/// let mut other = SipHasher13::new_with_key([8; 16]);
+/// other.write(b"key");
+///
+/// assert_ne!(hasher.finish(), other.finish())
+/// ```
+#[derive(Clone)]
+pub struct SipHasher13 {
+    v: [u64; 4],
+    tail: u64,
+    tail_len: usize,
+    len: usize,
+}
+
+impl SipHasher13 {
+    /// Create new SipHasher13 keyed with the little-endian halves of `key`
+    pub fn new_with_key(key: [u8; 16]) -> Self {
+        let (k0, k1) = key.split_at(8);
+        let k0 = u64::from_le_bytes(k0.try_into().expect("key half is 8 bytes"));
+        let k1 = u64::from_le_bytes(k1.try_into().expect("key half is 8 bytes"));
+        Self {
+            v: [
+                k0 ^ 0x736f6d6570736575,
+                k1 ^ 0x646f72616e646f6d,
+                k0 ^ 0x6c7967656e657261,
+                k1 ^ 0x7465646279746573,
+            ],
+            tail: 0,
+            tail_len: 0,
+            len: 0,
+        }
+    }
+
+    fn compress(&mut self, m: u64) {
+        self.v[3] ^= m;
+        sip_round(&mut self.v);
+        self.v[0] ^= m;
+    }
+}
+
+/// The state is derived from the secret key, so it is never shown.
+impl fmt::Debug for SipHasher13 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SipHasher13").finish_non_exhaustive()
+    }
+}
+
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+impl Hasher for SipHasher13 {
+    fn finish(&self) -> u64 {
+        let mut v = self.v;
+        let b = ((self.len as u64 & 0xff) << 56) | self.tail;
+        v[3] ^= b;
+        sip_round(&mut v);
+        v[0] ^= b;
+        v[2] ^= 0xff;
+        for _ in 0..3 {
+            sip_round(&mut v);
+        }
+        v[0] ^ v[1] ^ v[2] ^ v[3]
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.len = self.len.wrapping_add(bytes.len());
+        for byte in bytes {
+            self.tail |= (*byte as u64) << (8 * self.tail_len);
+            self.tail_len += 1;
+            if self.tail_len == 8 {
+                self.compress(self.tail);
+                self.tail = 0;
+                self.tail_len = 0;
+            }
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{JumpHasher, SipHasher13};
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    #[test]
+    fn test_matches_default_hasher() {
+        // DefaultHasher is SipHash-1-3 with a zero key.
+        let input: Vec<u8> = (0..64).collect();
+        for len in 0..input.len() {
+            let mut expected = DefaultHasher::new();
+            expected.write(&input[..len]);
+
+            let mut hasher = SipHasher13::new_with_key([0; 16]);
+            let (head, tail) = input[..len].split_at(len / 3);
+            hasher.write(head);
+            hasher.write(tail);
+
+            assert_eq!(hasher.finish(), expected.finish(), "{len} bytes");
+        }
+    }
+
+    #[test]
+    fn test_with_keyed_hash() {
+        let route = |secret: [u8; 16], key: u64| {
+            let mut hasher = JumpHasher::with_keyed_hash(1000, secret);
+            key.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let (first, second) = ([1; 16], [2; 16]);
+        let differ = (0..1000)
+            .filter(|key| route(first, *key) != route(second, *key))
+            .count();
+        assert!(differ > 900, "{differ}");
+        assert_eq!(
+            format!("{:?}", SipHasher13::new_with_key(first)),
+            "SipHasher13 { .. }"
+        );
+        for key in 0..1000 {
+            assert_eq!(route(first, key), route(first, key));
+            assert!(route(first, key) < 1000);
+        }
+    }
+}
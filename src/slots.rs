@@ -0,0 +1,745 @@
+use crate::{expected_movement, slots_for_target_load};
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::num::{IntErrorKind, NonZeroU32};
+use std::ops::Range;
+use std::str::FromStr;
+
+/// `Slots` is the number of buckets keys are distributed over.
+/// It is never zero, so every bucket computed for it is in `0..slots`.
+///
+/// `Slots` is `#[repr(transparent)]` over a `u32`, it has the same size and alignment as `u32`
+/// and `Option<Slots>` too. This is a stable guarantee, so `&[u32]` holding no zeros can be reinterpreted as `&[Slots]`.
+///
+/// Example:
+/// ```rust
+/// use jumpch::Slots;
+///
+/// let slots = Slots::from(1000);
+///
+/// assert_eq!(slots.get(), 1000)
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Slots(NonZeroU32);
+
+/// The smallest slot count, `NonZeroU32::MIN` on compilers which have it
+const ONE: NonZeroU32 = match NonZeroU32::new(1) {
+    Some(one) => one,
+    None => unreachable!(),
+};
+
+impl Slots {
+    /// Create new Slots, returns `None` if `slots` is zero
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert!(Slots::new(0).is_none());
+    /// assert_eq!(Slots::new(10).map(Slots::get), Some(10));
+    /// ```
+    pub const fn new(slots: u32) -> Option<Self> {
+        match NonZeroU32::new(slots) {
+            Some(slots) => Some(Self(slots)),
+            None => None,
+        }
+    }
+
+    /// Create new Slots from a wide integer, checking that it is neither zero nor more than `u32::MAX`
+    /// ```rust
+    /// use jumpch::{Slots, SlotsError};
+    ///
+    /// assert_eq!(Slots::checked_from(0), Err(SlotsError::ZeroSlots));
+    /// assert_eq!(Slots::checked_from(u64::MAX), Err(SlotsError::TooLarge));
+    /// assert_eq!(Slots::checked_from(10), Ok(Slots::from(10)));
+    /// ```
+    pub fn checked_from(slots: u64) -> Result<Self, SlotsError> {
+        let slots = u32::try_from(slots).map_err(|_| SlotsError::TooLarge)?;
+        Self::new(slots).ok_or(SlotsError::ZeroSlots)
+    }
+
+    /// Create new Slots like `From<u32>`, naming the config field `label` in the panic message.
+    ///
+    /// Panics with "`label` must be greater than 0" if `slots` is zero.
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from_labeled(10, "cache_slots").get(), 10);
+    /// ```
+    #[track_caller]
+    pub fn from_labeled(slots: u32, label: &str) -> Self {
+        match Self::new(slots) {
+            Some(slots) => slots,
+            None => panic!("{label} must be greater than 0"),
+        }
+    }
+
+    /// Create new Slots from `n` clamped into `[min, max]`, e.g. to enforce the slot count bounds of a deployment.
+    ///
+    /// Panics if `min` is zero or greater than `max`.
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::clamped(1, 4, 4096).get(), 4);
+    /// assert_eq!(Slots::clamped(10_000, 4, 4096).get(), 4096);
+    /// assert_eq!(Slots::clamped(100, 4, 4096).get(), 100);
+    /// ```
+    pub fn clamped(n: u32, min: u32, max: u32) -> Self {
+        assert!(min > 0, "min slots must be greater than 0");
+        assert!(
+            min <= max,
+            "min slots {min} must not exceed max slots {max}"
+        );
+        Self::from(n.clamp(min, max))
+    }
+
+    /// Create new Slots as `percent` of `max`, rounded down and clamped to at least 1,
+    /// e.g. for an autoscaler targeting 70% of the maximum partitions.
+    ///
+    /// Panics if `percent` is greater than 100.
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from_percent(4096, 25).get(), 1024);
+    /// assert_eq!(Slots::from_percent(10, 1).get(), 1);
+    /// ```
+    pub fn from_percent(max: u32, percent: u8) -> Self {
+        assert!(percent <= 100, "percent must be at most 100, got {percent}");
+        let slots = max as u64 * percent as u64 / 100;
+        Self::from((slots as u32).max(1))
+    }
+
+    /// Returns the number of slots as a primitive integer
+    pub const fn get(self) -> u32 {
+        self.0.get()
+    }
+
+    /// Adds `n` slots, saturating at `u32::MAX`
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from(10).saturating_add(5).get(), 15);
+    /// assert_eq!(Slots::from(u32::MAX - 1).saturating_add(5).get(), u32::MAX);
+    /// ```
+    pub const fn saturating_add(self, n: u32) -> Self {
+        match Self::new(self.get().saturating_add(n)) {
+            Some(slots) => slots,
+            // Adding to a non-zero count never gives zero.
+            None => unreachable!(),
+        }
+    }
+
+    /// Removes `n` slots, never going below one slot
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from(10).saturating_sub(5).get(), 5);
+    /// assert_eq!(Slots::from(10).saturating_sub(50).get(), 1);
+    /// ```
+    pub const fn saturating_sub(self, n: u32) -> Self {
+        match Self::new(self.get().saturating_sub(n)) {
+            Some(slots) => slots,
+            None => Self(ONE),
+        }
+    }
+
+    /// Multiplies the slots by `factor`, e.g. for `slots * replicas * vnodes` capacity planning.
+    /// Returns `None` if `factor` is zero or the product exceeds `u32::MAX`
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from(10).checked_mul(3), Some(Slots::from(30)));
+    /// assert_eq!(Slots::from(10).checked_mul(0), None);
+    /// assert_eq!(Slots::from(u32::MAX).checked_mul(2), None);
+    /// ```
+    pub const fn checked_mul(self, factor: u32) -> Option<Self> {
+        match self.get().checked_mul(factor) {
+            Some(slots) => Self::new(slots),
+            None => None,
+        }
+    }
+
+    /// Encodes the number of slots as 4 little-endian bytes, for binary formats without serde
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from(5).to_le_bytes(), [5, 0, 0, 0]);
+    /// ```
+    pub const fn to_le_bytes(&self) -> [u8; 4] {
+        self.get().to_le_bytes()
+    }
+
+    /// Decodes slots written by [`Slots::to_le_bytes`], checking that the count isn't zero
+    /// ```rust
+    /// use jumpch::{Slots, SlotsError};
+    ///
+    /// assert_eq!(Slots::from_le_bytes([5, 0, 0, 0]), Ok(Slots::from(5)));
+    /// assert_eq!(Slots::from_le_bytes([0; 4]), Err(SlotsError::ZeroSlots));
+    /// ```
+    pub const fn from_le_bytes(bytes: [u8; 4]) -> Result<Self, SlotsError> {
+        match Self::new(u32::from_le_bytes(bytes)) {
+            Some(slots) => Ok(slots),
+            None => Err(SlotsError::ZeroSlots),
+        }
+    }
+
+    /// Returns the number of slots as a `NonZeroU32`, e.g. for config fields of that type
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from(5).as_nonzero().get(), 5);
+    /// assert_eq!(Slots::from(Slots::from(5).as_nonzero()), Slots::from(5));
+    /// ```
+    pub const fn as_nonzero(&self) -> NonZeroU32 {
+        self.0
+    }
+
+    /// Returns every bucket index, `0..slots`
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from(3).buckets().collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// ```
+    pub const fn buckets(self) -> Range<u32> {
+        0..self.get()
+    }
+
+    /// Returns the number of slots as a float, for capacity math
+    pub const fn as_f64(&self) -> f64 {
+        self.get() as f64
+    }
+
+    /// Returns the mean number of keys per bucket when `total_keys` are distributed over the slots
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from(4).expected_load(100), 25.0);
+    /// ```
+    pub fn expected_load(&self, total_keys: u64) -> f64 {
+        total_keys as f64 / self.as_f64()
+    }
+
+    /// Returns the smallest slot count below `self` that keeps the mean load of `total_keys`
+    /// at or under `target_per_bucket`, or `None` if shrinking at all would exceed the target.
+    ///
+    /// Panics if `target_per_bucket` is zero.
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from(100).try_shrink_to(1000, 100), Some(Slots::from(10)));
+    /// assert_eq!(Slots::from(10).try_shrink_to(1000, 100), None);
+    /// ```
+    pub fn try_shrink_to(self, total_keys: u64, target_per_bucket: u64) -> Option<Self> {
+        let smallest = slots_for_target_load(total_keys, target_per_bucket);
+        (smallest < self).then_some(smallest)
+    }
+
+    /// Describes the transition from `self` to `to` in one sentence for runbooks and change tickets,
+    /// with the share of moving keys from [`expected_movement`](crate::expected_movement)
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(
+    ///     Slots::from(8).describe_movement(Slots::from(16)),
+    ///     "Growing from 8 to 16 buckets; ~50% of keys will move to new buckets."
+    /// );
+    /// ```
+    pub fn describe_movement(self, to: Slots) -> String {
+        let percent = expected_movement(self, to) * 100.0;
+        let share = if percent < 1.0 {
+            "<1%".to_string()
+        } else {
+            format!("~{percent:.0}%")
+        };
+        let (from, to) = (self.get(), to.get());
+        match from.cmp(&to) {
+            Ordering::Less => format!(
+                "Growing from {from} to {to} buckets; {share} of keys will move to new buckets."
+            ),
+            Ordering::Greater => format!(
+                "Shrinking from {from} to {to} buckets; {share} of keys will move off removed buckets."
+            ),
+            Ordering::Equal => format!("Keeping {from} buckets; no keys will move."),
+        }
+    }
+
+    /// Returns the smallest power of two slot count greater than or equal to `self`,
+    /// or `None` if it exceeds `u32::MAX`
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from(100).checked_next_power_of_two(), Some(Slots::from(128)));
+    /// assert_eq!(Slots::from(u32::MAX).checked_next_power_of_two(), None);
+    /// ```
+    pub const fn checked_next_power_of_two(self) -> Option<Self> {
+        match self.get().checked_next_power_of_two() {
+            Some(slots) => Self::new(slots),
+            None => None,
+        }
+    }
+
+    /// Returns the smallest power of two slot count greater than or equal to `self`.
+    ///
+    /// Panics if the result exceeds `u32::MAX`.
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// assert_eq!(Slots::from(100).next_power_of_two().get(), 128);
+    /// assert_eq!(Slots::from(64).next_power_of_two().get(), 64);
+    /// ```
+    pub const fn next_power_of_two(self) -> Self {
+        match self.checked_next_power_of_two() {
+            Some(slots) => slots,
+            None => panic!("next power of two slots exceeds u32::MAX"),
+        }
+    }
+}
+
+/// Builds [`Slots`] from a computed `u32` expression, e.g. `nodes * replicas / 2`,
+/// panicking at the call site with the expression text if it evaluates to zero.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{slots, Slots};
+///
+/// let (nodes, replicas) = (4, 3);
+///
+/// assert_eq!(slots!(nodes * replicas / 2), Slots::from(6))
+/// ```
+///
+/// A zero count panics naming the expression:
+/// ```rust,should_panic
+/// use jumpch::slots;
+///
+/// let nodes = 1;
+/// slots!(nodes / 2); // panics with "slots!(nodes / 2) evaluated to 0"
+/// ```
+#[macro_export]
+macro_rules! slots {
+    ($slots:expr $(,)?) => {{
+        let slots: u32 = $slots;
+        match $crate::Slots::new(slots) {
+            Some(slots) => slots,
+            None => panic!(
+                "{}",
+                concat!("slots!(", stringify!($slots), ") evaluated to 0")
+            ),
+        }
+    }};
+}
+
+impl From<u32> for Slots {
+    /// Panics if `slots` is zero.
+    fn from(slots: u32) -> Self {
+        Self::new(slots).expect("slots must be greater than 0")
+    }
+}
+
+impl From<NonZeroU32> for Slots {
+    fn from(slots: NonZeroU32) -> Self {
+        Self(slots)
+    }
+}
+
+impl From<&Slots> for Slots {
+    /// Copies borrowed slots, so `hash(key, &slots)` works in generic code.
+    /// ```rust
+    /// use jumpch::{hash, Slots};
+    ///
+    /// let slots = Slots::from(10);
+    ///
+    /// assert_eq!(hash(1, &slots), hash(1, slots))
+    /// ```
+    fn from(slots: &Slots) -> Self {
+        *slots
+    }
+}
+
+impl TryFrom<u64> for Slots {
+    type Error = SlotsError;
+
+    fn try_from(slots: u64) -> Result<Self, Self::Error> {
+        Self::checked_from(slots)
+    }
+}
+
+impl TryFrom<usize> for Slots {
+    type Error = SlotsError;
+
+    fn try_from(slots: usize) -> Result<Self, Self::Error> {
+        u64::try_from(slots)
+            .map_err(|_| SlotsError::TooLarge)
+            .and_then(Self::checked_from)
+    }
+}
+
+impl FromStr for Slots {
+    type Err = SlotsError;
+
+    /// Parses a decimal slot count, e.g. from an environment variable.
+    /// ```rust
+    /// use jumpch::{Slots, SlotsError};
+    ///
+    /// assert_eq!("1000".parse::<Slots>(), Ok(Slots::from(1000)));
+    /// assert_eq!("0".parse::<Slots>(), Err(SlotsError::ZeroSlots));
+    /// assert_eq!("abc".parse::<Slots>(), Err(SlotsError::NotANumber));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let slots = s.parse::<u32>().map_err(|err| match err.kind() {
+            IntErrorKind::PosOverflow => SlotsError::TooLarge,
+            _ => SlotsError::NotANumber,
+        })?;
+        Self::new(slots).ok_or(SlotsError::ZeroSlots)
+    }
+}
+
+impl IntoIterator for &Slots {
+    type Item = u32;
+    type IntoIter = Range<u32>;
+
+    /// Iterates every bucket index, see [`Slots::buckets`].
+    /// ```rust
+    /// use jumpch::Slots;
+    ///
+    /// let mut buckets = Vec::new();
+    /// for bucket in &Slots::from(3) {
+    ///     buckets.push(bucket);
+    /// }
+    ///
+    /// assert_eq!(buckets, vec![0, 1, 2])
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        self.buckets()
+    }
+}
+
+impl From<Slots> for u32 {
+    fn from(slots: Slots) -> Self {
+        slots.get()
+    }
+}
+
+/// The reason a value can't be used as `Slots`.
+/// New validation rules may add variants, so matches need a wildcard arm.
+///
+/// Example:
+/// ```rust
+/// use jumpch::{Slots, SlotsError};
+///
+/// let message = match Slots::checked_from(0) {
+///     Ok(_) => "valid",
+///     Err(SlotsError::ZeroSlots) => "zero",
+///     Err(SlotsError::TooLarge) => "too large",
+///     Err(_) => "invalid",
+/// };
+///
+/// assert_eq!(message, "zero")
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SlotsError {
+    /// The slot count is zero.
+    ZeroSlots,
+    /// The slot count doesn't fit into `u32`.
+    TooLarge,
+    /// The parsed string isn't a decimal integer.
+    NotANumber,
+}
+
+impl fmt::Display for SlotsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlotsError::ZeroSlots => f.write_str("slots must be greater than 0"),
+            SlotsError::TooLarge => f.write_str("slots exceeds u32::MAX"),
+            SlotsError::NotANumber => f.write_str("slots must be a decimal integer"),
+        }
+    }
+}
+
+impl Error for SlotsError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Slots, SlotsError};
+    use std::error::Error;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(Slots::new(0), None);
+        assert_eq!(Slots::new(1).map(Slots::get), Some(1));
+        assert_eq!(Slots::new(u32::MAX).map(Slots::get), Some(u32::MAX));
+    }
+
+    #[test]
+    fn test_checked_from() {
+        assert_eq!(Slots::checked_from(0), Err(SlotsError::ZeroSlots));
+        assert_eq!(
+            Slots::checked_from(u32::MAX as u64 + 1),
+            Err(SlotsError::TooLarge)
+        );
+        assert_eq!(Slots::checked_from(u64::MAX), Err(SlotsError::TooLarge));
+        assert_eq!(Slots::checked_from(1000), Ok(Slots::from(1000)));
+        assert_eq!(
+            Slots::checked_from(u32::MAX as u64),
+            Ok(Slots::from(u32::MAX))
+        );
+    }
+
+    #[test]
+    fn test_try_from() {
+        assert_eq!(Slots::try_from(0usize), Err(SlotsError::ZeroSlots));
+        assert_eq!(Slots::try_from(u64::MAX), Err(SlotsError::TooLarge));
+        assert_eq!(Slots::try_from(10usize), Ok(Slots::from(10)));
+    }
+
+    #[test]
+    fn test_layout() {
+        use std::mem::{align_of, size_of};
+
+        assert_eq!(size_of::<Slots>(), size_of::<u32>());
+        assert_eq!(align_of::<Slots>(), align_of::<u32>());
+        assert_eq!(size_of::<Option<Slots>>(), size_of::<u32>());
+
+        let raw: &[u32] = &[1, 10, u32::MAX];
+        // SAFETY: `Slots` is `#[repr(transparent)]` over `NonZeroU32`, which has the layout of `u32`,
+        // and every value of `raw` is non-zero, so each element is a valid `Slots` for the same lifetime.
+        let slots: &[Slots] = unsafe { std::slice::from_raw_parts(raw.as_ptr().cast(), raw.len()) };
+        assert_eq!(
+            slots,
+            [Slots::from(1), Slots::from(10), Slots::from(u32::MAX)]
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("1000".parse::<Slots>(), Ok(Slots::from(1000)));
+        assert_eq!("4294967295".parse::<Slots>(), Ok(Slots::from(u32::MAX)));
+        assert_eq!("0".parse::<Slots>(), Err(SlotsError::ZeroSlots));
+        assert_eq!("4294967296".parse::<Slots>(), Err(SlotsError::TooLarge));
+        for invalid in ["abc", "", "-1", " 10", "1.5"] {
+            assert_eq!(invalid.parse::<Slots>(), Err(SlotsError::NotANumber));
+        }
+        assert_eq!(
+            "abc".parse::<Slots>().unwrap_err().to_string(),
+            "slots must be a decimal integer"
+        );
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            SlotsError::ZeroSlots.to_string(),
+            "slots must be greater than 0"
+        );
+        assert_eq!(SlotsError::TooLarge.to_string(), "slots exceeds u32::MAX");
+
+        let boxed: Box<dyn Error> = Box::new(SlotsError::ZeroSlots);
+        assert_eq!(boxed.to_string(), "slots must be greater than 0");
+
+        fn parse(slots: u64) -> Result<Slots, Box<dyn Error>> {
+            Ok(Slots::checked_from(slots)?)
+        }
+        assert!(parse(0).is_err());
+        assert_eq!(parse(5).unwrap(), Slots::from(5));
+    }
+
+    #[test]
+    fn test_saturating() {
+        assert_eq!(Slots::from(1).saturating_add(0), Slots::from(1));
+        assert_eq!(Slots::from(8).saturating_add(8), Slots::from(16));
+        assert_eq!(
+            Slots::from(u32::MAX - 3).saturating_add(10),
+            Slots::from(u32::MAX)
+        );
+        assert_eq!(
+            Slots::from(u32::MAX).saturating_add(u32::MAX),
+            Slots::from(u32::MAX)
+        );
+
+        assert_eq!(Slots::from(16).saturating_sub(8), Slots::from(8));
+        assert_eq!(Slots::from(16).saturating_sub(15), Slots::from(1));
+        assert_eq!(Slots::from(16).saturating_sub(16), Slots::from(1));
+        assert_eq!(Slots::from(16).saturating_sub(u32::MAX), Slots::from(1));
+    }
+
+    #[test]
+    fn test_checked_mul() {
+        assert_eq!(Slots::from(16).checked_mul(4), Some(Slots::from(64)));
+        assert_eq!(Slots::from(1).checked_mul(1), Some(Slots::from(1)));
+        assert_eq!(Slots::from(16).checked_mul(0), None);
+        assert_eq!(Slots::from(1 << 16).checked_mul(1 << 16), None);
+        assert_eq!(
+            Slots::from(1 << 16).checked_mul((1 << 16) - 1),
+            Some(Slots::from(u32::MAX - (1 << 16) + 1))
+        );
+    }
+
+    #[test]
+    fn test_slots_macro() {
+        assert_eq!(slots!(2 * 3), Slots::from(6));
+        assert_eq!(slots!(u32::MAX), Slots::from(u32::MAX));
+        let (nodes, replicas) = (5u32, 2);
+        assert_eq!(slots!(nodes * replicas / 2,), Slots::from(5));
+
+        let counts = [5u32];
+        assert_eq!(
+            slots!({
+                let (base, extra) = (counts[0], 3);
+                base + extra
+            }),
+            Slots::from(8)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = ") evaluated to 0")]
+    fn test_slots_macro_zero_block() {
+        let counts = [0u32];
+        slots!({ counts[0] });
+    }
+
+    #[test]
+    #[should_panic(expected = "slots!(nodes / 2) evaluated to 0")]
+    fn test_slots_macro_zero() {
+        let nodes = 1;
+        slots!(nodes / 2);
+    }
+
+    #[test]
+    fn test_clamped() {
+        assert_eq!(Slots::clamped(0, 4, 4096), Slots::from(4));
+        assert_eq!(Slots::clamped(3, 4, 4096), Slots::from(4));
+        assert_eq!(Slots::clamped(4097, 4, 4096), Slots::from(4096));
+        assert_eq!(Slots::clamped(u32::MAX, 4, 4096), Slots::from(4096));
+        for n in [4, 5, 100, 4096] {
+            assert_eq!(Slots::clamped(n, 4, 4096).get(), n);
+        }
+        assert_eq!(Slots::clamped(0, 1, 1), Slots::from(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "min slots must be greater than 0")]
+    fn test_clamped_zero_min() {
+        Slots::clamped(10, 0, 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "min slots 10 must not exceed max slots 5")]
+    fn test_clamped_inverted() {
+        Slots::clamped(7, 10, 5);
+    }
+
+    #[test]
+    fn test_from_percent() {
+        assert_eq!(Slots::from_percent(100, 70).get(), 70);
+        assert_eq!(Slots::from_percent(10, 1).get(), 1);
+        assert_eq!(Slots::from_percent(10, 0).get(), 1);
+        assert_eq!(Slots::from_percent(0, 50).get(), 1);
+        assert_eq!(Slots::from_percent(999, 50).get(), 499);
+        assert_eq!(Slots::from_percent(u32::MAX, 100).get(), u32::MAX);
+        assert_eq!(Slots::from_percent(u32::MAX, 50).get(), u32::MAX / 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "percent must be at most 100, got 150")]
+    fn test_from_percent_above_100() {
+        Slots::from_percent(100, 150);
+    }
+
+    #[test]
+    fn test_le_bytes() {
+        for slots in [1, 2, 255, 256, 1000, u32::MAX] {
+            let slots = Slots::from(slots);
+            assert_eq!(slots.to_le_bytes(), slots.get().to_le_bytes());
+            assert_eq!(Slots::from_le_bytes(slots.to_le_bytes()), Ok(slots));
+        }
+        assert_eq!(Slots::from_le_bytes([0; 4]), Err(SlotsError::ZeroSlots));
+    }
+
+    #[test]
+    fn test_buckets() {
+        for slots in [1, 2, 10, 1000] {
+            let slots = Slots::from(slots);
+            assert_eq!(slots.buckets().len(), slots.get() as usize);
+            assert!((&slots).into_iter().eq(0..slots.get()));
+        }
+        assert_eq!(Slots::from(u32::MAX).buckets().last(), Some(u32::MAX - 1));
+    }
+
+    #[test]
+    fn test_expected_load() {
+        assert_eq!(Slots::from(4).as_f64(), 4.0);
+        assert_eq!(Slots::from(4).expected_load(100), 25.0);
+        assert_eq!(Slots::from(3).expected_load(0), 0.0);
+        assert_eq!(Slots::from(8).expected_load(4), 0.5);
+    }
+
+    #[test]
+    fn test_try_shrink_to() {
+        let slots = Slots::from(64);
+        assert_eq!(slots.try_shrink_to(1000, 100), Some(Slots::from(10)));
+        assert_eq!(slots.try_shrink_to(0, 100), Some(Slots::from(1)));
+        assert_eq!(slots.try_shrink_to(6300, 100), Some(Slots::from(63)));
+        assert_eq!(slots.try_shrink_to(6301, 100), None);
+        assert_eq!(slots.try_shrink_to(6400, 100), None);
+        assert_eq!(slots.try_shrink_to(10_000, 100), None);
+        assert_eq!(Slots::from(1).try_shrink_to(0, 1), None);
+    }
+
+    #[test]
+    fn test_describe_movement() {
+        let growing = Slots::from(8).describe_movement(Slots::from(16));
+        assert!(growing.contains('8') && growing.contains("16"));
+        assert!(growing.contains("~50%"));
+
+        assert_eq!(
+            Slots::from(10).describe_movement(Slots::from(4)),
+            "Shrinking from 10 to 4 buckets; ~60% of keys will move off removed buckets."
+        );
+        assert_eq!(
+            Slots::from(1000).describe_movement(Slots::from(1001)),
+            "Growing from 1000 to 1001 buckets; <1% of keys will move to new buckets."
+        );
+        assert_eq!(
+            Slots::from(8).describe_movement(Slots::from(8)),
+            "Keeping 8 buckets; no keys will move."
+        );
+    }
+
+    #[test]
+    fn test_next_power_of_two() {
+        assert_eq!(Slots::from(1).next_power_of_two(), Slots::from(1));
+        assert_eq!(Slots::from(3).next_power_of_two(), Slots::from(4));
+        assert_eq!(Slots::from(100).next_power_of_two(), Slots::from(128));
+        assert_eq!(Slots::from(128).next_power_of_two(), Slots::from(128));
+        assert_eq!(
+            Slots::from(1 << 31).next_power_of_two(),
+            Slots::from(1 << 31)
+        );
+        assert_eq!(Slots::from((1 << 31) + 1).checked_next_power_of_two(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "next power of two slots exceeds u32::MAX")]
+    fn test_next_power_of_two_overflow() {
+        Slots::from(u32::MAX).next_power_of_two();
+    }
+
+    #[test]
+    #[should_panic(expected = "cache_slots must be greater than 0")]
+    fn test_from_labeled_zero() {
+        Slots::from_labeled(0, "cache_slots");
+    }
+
+    #[test]
+    fn test_from_labeled() {
+        assert_eq!(Slots::from_labeled(1, "cache_slots"), Slots::from(1));
+        assert_eq!(Slots::from_labeled(u32::MAX, "cache_slots").get(), u32::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "slots must be greater than 0")]
+    fn test_from_zero() {
+        let _ = Slots::from(0);
+    }
+}
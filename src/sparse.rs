@@ -0,0 +1,124 @@
+use crate::sharder::{index_in, slots_for};
+use std::collections::BTreeMap;
+use std::hash::Hash;
+
+/// `SparseSharder` routes keys to nodes labeled by arbitrary, non-contiguous ids.
+/// The dense jump hash bucket indexes the nodes sorted by id, so adding a node with
+/// an id higher than all others moves only the keys that go to the new node.
+///
+/// Other changes are not minimal: removing a node, or adding one with an id below the highest,
+/// shifts the index of every node with a higher id, so keys of unchanged nodes move too.
+/// To take a node out without reshuffling, keep its id and reroute its keys instead,
+/// e.g. with [`route_excluding`](crate::route_excluding) over a dense bucket layout.
+///
+/// Example:
+/// ```rust
+/// use std::collections::BTreeMap;
+/// use jumpch::SparseSharder;
+///
+/// let sharder = SparseSharder::new(BTreeMap::from([(10, "a"), (20, "b"), (30, "c")]));
+///
+/// assert!([10, 20, 30].contains(&sharder.id(&"key")))
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseSharder<T> {
+    nodes: Vec<(u32, T)>,
+}
+
+impl<T> SparseSharder<T> {
+    /// Create new SparseSharder over the nodes of `map`.
+    ///
+    /// Panics if `map` is empty or has more than `u32::MAX` nodes.
+    pub fn new(map: BTreeMap<u32, T>) -> Self {
+        let nodes: Vec<_> = map.into_iter().collect();
+        slots_for(&nodes);
+        Self { nodes }
+    }
+
+    /// Returns the `(id, node)` pairs sorted by id
+    pub fn nodes(&self) -> &[(u32, T)] {
+        &self.nodes
+    }
+
+    /// Returns the id of the node of `key`
+    pub fn id<K: Hash + ?Sized>(&self, key: &K) -> u32 {
+        self.entry(key).0
+    }
+
+    /// Returns the node of `key`
+    pub fn route<K: Hash + ?Sized>(&self, key: &K) -> &T {
+        &self.entry(key).1
+    }
+
+    fn entry<K: Hash + ?Sized>(&self, key: &K) -> &(u32, T) {
+        &self.nodes[index_in(key, slots_for(&self.nodes))]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SparseSharder;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_route() {
+        let map = BTreeMap::from([(3, "a"), (17, "b"), (90, "c")]);
+        let sharder = SparseSharder::new(map.clone());
+        for key in 0..1000u64 {
+            assert_eq!(sharder.route(&key), &map[&sharder.id(&key)]);
+        }
+    }
+
+    #[test]
+    fn test_add_high_id_is_stable() {
+        let mut map = BTreeMap::from([(3, "a"), (17, "b"), (90, "c")]);
+        let before = SparseSharder::new(map.clone());
+        map.insert(1000, "d");
+        let after = SparseSharder::new(map);
+
+        let mut moved = 0;
+        for key in 0..1000u64 {
+            let (old, new) = (before.id(&key), after.id(&key));
+            if old != new {
+                assert_eq!(new, 1000);
+                moved += 1;
+            }
+        }
+        assert!(moved > 0 && moved < 400);
+    }
+
+    #[test]
+    fn test_remove_or_insert_low_id_reshuffles() {
+        let map = BTreeMap::from([(3, "a"), (17, "b"), (90, "c"), (200, "d")]);
+        let before = SparseSharder::new(map.clone());
+
+        let mut removed = map.clone();
+        removed.remove(&3);
+        let after_remove = SparseSharder::new(removed);
+
+        let mut inserted = map;
+        inserted.insert(10, "e");
+        let after_insert = SparseSharder::new(inserted);
+
+        let (mut kept_moved, mut inserted_moved) = (0, 0);
+        for key in 0..1000u64 {
+            let old = before.id(&key);
+            if old != 3 && after_remove.id(&key) != old {
+                kept_moved += 1;
+            }
+            let new = after_insert.id(&key);
+            if new != old && new != 10 {
+                inserted_moved += 1;
+            }
+        }
+        // Keys of nodes that are still there move, unlike on a high-id insertion.
+        assert!(kept_moved > 300, "{kept_moved}");
+        assert!(inserted_moved > 300, "{inserted_moved}");
+    }
+
+    #[test]
+    #[should_panic(expected = "sharder must have at least one node")]
+    fn test_empty() {
+        SparseSharder::<&str>::new(BTreeMap::new());
+    }
+}
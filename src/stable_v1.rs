@@ -0,0 +1,71 @@
+//! Version 1 of the routing algorithm, frozen forever.
+//!
+//! [`crate::hash`] may change in a future major version, e.g. by adopting integer math,
+//! which would move keys. Code persisting shard assignments can route through `stable_v1::hash`
+//! instead, it returns the buckets of jumpch 1.0 in every later release. Only the signature differs:
+//! 1.0 took the slot count as an `i64` and returned `u32::MAX` for zero or negative counts,
+//! which [`Slots`] rules out.
+//! Algorithm changes go into a new `stable_v2` module, this one is never touched.
+
+use crate::Slots;
+
+/// The multiplier of the version 1 generator.
+const MULTIPLIER: u64 = 2862933555777941757;
+
+/// Routes `key` to the bucket jumpch 1.0's `hash(key, slots as i64)` returned, the buckets never change
+/// between crate versions.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::stable_v1;
+///
+/// assert_eq!(stable_v1::hash(123456, 1000), 984)
+/// ```
+pub fn hash<S: Into<Slots>>(mut key: u64, slots: S) -> u32 {
+    let slots = slots.into().get() as i64;
+    let (mut b, mut j) = (-1i64, 0i64);
+    while j < slots {
+        b = j;
+        key = key.wrapping_mul(MULTIPLIER).wrapping_add(1);
+        j = ((b.wrapping_add(1) as f64) * (((1u64 << 31) as f64) / (((key >> 33) + 1) as f64)))
+            as i64;
+    }
+    b as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stable_v1;
+
+    /// Never edit these, a mismatch means version 1 routing changed.
+    const VECTORS: &[(u64, u32, u32)] = &[
+        (0, 1, 0),
+        (1, 10, 6),
+        (42, 65536, 5747),
+        (123456, 1000, 984),
+        (u64::MAX, 100, 92),
+        (0xdead_beef, u32::MAX, 1452406526),
+        (u64::MAX, u32::MAX, 2680453518),
+        (9_876_543_210, 7, 6),
+    ];
+
+    #[test]
+    fn test_vectors() {
+        for (key, slots, bucket) in VECTORS {
+            assert_eq!(
+                stable_v1::hash(*key, *slots),
+                *bucket,
+                "key {key}, slots {slots}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches_current() {
+        for key in 0..1000 {
+            for slots in [1, 2, 10, 1000, u32::MAX] {
+                assert_eq!(stable_v1::hash(key, slots), crate::hash(key, slots));
+            }
+        }
+    }
+}
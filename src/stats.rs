@@ -0,0 +1,134 @@
+use crate::chi_squared;
+
+/// Runs a chi-squared goodness-of-fit test of `counts` against a uniform distribution.
+/// Returns `true` if the counts are consistent with uniform at the given `significance` level (e.g. `0.01`).
+/// Fewer than two buckets are always uniform.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{distribution, is_uniform};
+///
+/// assert!(is_uniform(&distribution(0..100_000, 100.into()), 0.01));
+/// assert!(!is_uniform(&[100, 100, 1000], 0.01))
+/// ```
+pub fn is_uniform(counts: &[u64], significance: f64) -> bool {
+    if counts.len() < 2 {
+        return true;
+    }
+
+    let degrees_of_freedom = (counts.len() - 1) as f64;
+    let p_value = upper_regularized_gamma(degrees_of_freedom / 2.0, chi_squared(counts) / 2.0);
+    p_value >= significance
+}
+
+/// Q(a, x), the chi-squared survival function for `a = df / 2` and `x = chi2 / 2`.
+fn upper_regularized_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+
+    if x < a + 1.0 {
+        1.0 - lower_gamma_series(a, x)
+    } else {
+        upper_gamma_continued_fraction(a, x)
+    }
+}
+
+fn lower_gamma_series(a: f64, x: f64) -> f64 {
+    let (mut sum, mut term, mut n) = (1.0 / a, 1.0 / a, a);
+    for _ in 0..1000 {
+        n += 1.0;
+        term *= x / n;
+        sum += term;
+        if term.abs() < sum.abs() * f64::EPSILON {
+            break;
+        }
+    }
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+// Lentz's method, see Numerical Recipes 6.2.
+fn upper_gamma_continued_fraction(a: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..1000 {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < f64::EPSILON {
+            break;
+        }
+    }
+    (-x + a * x.ln() - ln_gamma(a)).exp() * h
+}
+
+// Lanczos approximation, accurate to about 15 digits for positive arguments.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .fold(1.000000000190015, |sum, (i, c)| {
+            sum + c / (x + 1.0 + i as f64)
+        });
+    -tmp + (2.5066282746310005 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::upper_regularized_gamma;
+    use crate::{distribution, is_uniform, Slots};
+
+    #[test]
+    fn test_survival_function() {
+        // Critical values of the chi-squared distribution at p = 0.05.
+        for (df, critical) in [(1.0, 3.841), (10.0, 18.307), (99.0, 123.225)] {
+            let p_value = upper_regularized_gamma(df / 2.0, critical / 2.0);
+            assert!((p_value - 0.05).abs() < 1e-3, "df {df}: {p_value}");
+        }
+    }
+
+    #[test]
+    fn test_uniform() {
+        let counts = distribution(0..1_000_000, Slots::from(1000));
+        assert!(is_uniform(&counts, 0.01));
+    }
+
+    #[test]
+    fn test_skewed() {
+        let mut counts = distribution(0..1_000_000, Slots::from(1000));
+        counts[0] += 500;
+        assert!(!is_uniform(&counts, 0.01));
+        assert!(!is_uniform(&[0, 0, 0, 1000], 0.01));
+    }
+
+    #[test]
+    fn test_trivial() {
+        assert!(is_uniform(&[], 0.01));
+        assert!(is_uniform(&[42], 0.01));
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{hash, Slots};
+use futures_util::{Stream, StreamExt};
+
+/// Routes every key of an async `stream` as it arrives, yielding `(key, bucket)` pairs.
+/// Jump hashing itself is synchronous, so this is only a `map` adapter for async pipelines.
+///
+/// Usage example:
+/// ```rust
+/// use futures_util::{stream, StreamExt};
+/// use jumpch::{hash, route_stream};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let routed: Vec<_> = route_stream(stream::iter([123456]), 1000.into()).collect().await;
+///
+/// assert_eq!(routed, vec![(123456, hash(123456, 1000))])
+/// # })
+/// ```
+pub fn route_stream<S: Stream<Item = u64>>(
+    stream: S,
+    slots: Slots,
+) -> impl Stream<Item = (u64, u32)> {
+    stream.map(move |key| (key, hash(key, slots)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, route_stream, Slots};
+    use futures_util::{stream, StreamExt};
+
+    #[tokio::test]
+    async fn test_route_stream() {
+        let slots = Slots::from(10);
+        let keys = [1, 2, 3, 42, 123456];
+
+        let routed: Vec<_> = route_stream(stream::iter(keys), slots).collect().await;
+
+        let expected: Vec<_> = keys.iter().map(|key| (*key, hash(*key, slots))).collect();
+        assert_eq!(routed, expected);
+    }
+}
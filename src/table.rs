@@ -0,0 +1,45 @@
+/// Precomputes the bucket of every byte key for a fixed slot count at compile time.
+/// Expands to a `[u32; 256]` where entry `k` is [`hash`](crate::hash)`(k as u64, slots)`,
+/// so routing a byte key becomes a single indexed load.
+///
+/// Fails to compile if `slots` is zero.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash, jump_table};
+///
+/// const TABLE: [u32; 256] = jump_table!(slots = 8);
+///
+/// assert_eq!(TABLE[42], hash(42, 8))
+/// ```
+#[macro_export]
+macro_rules! jump_table {
+    (slots = $slots:expr) => {{
+        const TABLE: [u32; 256] = {
+            let mut table = [0u32; 256];
+            let mut key = 0;
+            while key < table.len() {
+                table[key] = $crate::hash_const(key as u64, $slots);
+                key += 1;
+            }
+            table
+        };
+        TABLE
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hash;
+
+    #[test]
+    fn test_jump_table_matches_hash() {
+        const EIGHT: [u32; 256] = jump_table!(slots = 8);
+        for key in 0..=u8::MAX {
+            assert_eq!(EIGHT[key as usize], hash(key as u64, 8));
+        }
+
+        let one = jump_table!(slots = 1);
+        assert!(one.iter().all(|bucket| *bucket == 0));
+    }
+}
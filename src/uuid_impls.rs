@@ -0,0 +1,30 @@
+use crate::{hash128, Slots};
+use uuid::Uuid;
+
+/// Routes a `Uuid` on all of its 128 bits, see [`hash128`].
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{hash128, hash_uuid};
+/// use uuid::Uuid;
+///
+/// let id = Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+///
+/// assert_eq!(hash_uuid(id, 1000), hash128(id.as_u128(), 1000))
+/// ```
+pub fn hash_uuid<S: Into<Slots>>(id: Uuid, slots: S) -> u32 {
+    hash128(id.as_u128(), slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash128, hash_uuid};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_hash_uuid() {
+        let id = Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+        assert_eq!(hash_uuid(id, 1000), 841);
+        assert_eq!(hash_uuid(Uuid::nil(), 1000), hash128(0, 1000));
+    }
+}
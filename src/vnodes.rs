@@ -0,0 +1,101 @@
+use crate::{hash, Slots};
+
+/// `VirtualSharder` routes keys over `slots * vnodes` virtual buckets and maps them back to real slots.
+/// Every real slot owns `vnodes` contiguous virtual buckets, so growing the real slot count
+/// still moves keys only onto the new slot, as plain jump hashing does.
+///
+/// Jump hashing is already balanced in expectation, virtual buckets don't change that expectation,
+/// so measure the distribution of your own key set before relying on them.
+///
+/// Example:
+/// ```rust
+/// use jumpch::VirtualSharder;
+///
+/// let sharder = VirtualSharder::new(4.into(), 16);
+///
+/// assert!(sharder.route(123456) < 4)
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VirtualSharder {
+    slots: Slots,
+    vnodes: u32,
+}
+
+impl VirtualSharder {
+    /// Create new VirtualSharder with `vnodes` virtual buckets per real slot.
+    ///
+    /// Panics if `vnodes` is zero or `slots * vnodes` exceeds `u32::MAX`.
+    pub fn new(slots: Slots, vnodes: u32) -> Self {
+        assert!(vnodes > 0, "vnodes must be greater than 0");
+        assert!(
+            slots.get().checked_mul(vnodes).is_some(),
+            "slots * vnodes must not exceed u32::MAX"
+        );
+        Self { slots, vnodes }
+    }
+
+    /// Returns the number of real slots
+    pub fn slots(&self) -> Slots {
+        self.slots
+    }
+
+    /// Returns the number of virtual buckets per real slot
+    pub fn vnodes(&self) -> u32 {
+        self.vnodes
+    }
+
+    /// Returns the real slot of `key`
+    pub fn route(&self, key: u64) -> u32 {
+        hash(key, self.slots.get() * self.vnodes) / self.vnodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash, Slots, VirtualSharder};
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn keys() -> impl Iterator<Item = u64> {
+        (0..10_000u64).map(|i| {
+            let mut hasher = DefaultHasher::new();
+            i.hash(&mut hasher);
+            hasher.finish()
+        })
+    }
+
+    #[test]
+    fn test_distribution() {
+        for slots in 2..=4 {
+            let sharder = VirtualSharder::new(Slots::from(slots), 16);
+            let mut virtual_counts = vec![0u64; slots as usize];
+            let mut plain_counts = vec![0u64; slots as usize];
+            for key in keys() {
+                virtual_counts[sharder.route(key) as usize] += 1;
+                plain_counts[hash(key, slots) as usize] += 1;
+            }
+
+            let mean = 10_000 / slots as u64;
+            for (virtual_count, plain_count) in virtual_counts.iter().zip(&plain_counts) {
+                assert!(virtual_count.abs_diff(mean) < mean / 10);
+                assert!(plain_count.abs_diff(mean) < mean / 10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_growth_moves_to_new_slot() {
+        let from = VirtualSharder::new(Slots::from(3), 16);
+        let to = VirtualSharder::new(Slots::from(4), 16);
+        for key in keys() {
+            let (old, new) = (from.route(key), to.route(key));
+            assert!(old == new || new == 3);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "vnodes must be greater than 0")]
+    fn test_zero_vnodes() {
+        VirtualSharder::new(Slots::from(3), 0);
+    }
+}
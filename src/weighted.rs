@@ -0,0 +1,288 @@
+use crate::hash;
+use crate::seeded::mix64;
+
+/// Routes `key` to a node index with probability proportional to its weight in `weights`.
+/// The key is jump hashed over `sum(weights)` buckets and node `i` owns a contiguous range of `weights[i]` buckets,
+/// so growing the weight of the last node, or appending a node, moves keys only onto that node.
+///
+/// A zero weight owns no buckets, so drained or disabled nodes never get a key.
+///
+/// Panics if the weights sum to zero or to more than `u32::MAX`, see [`try_weighted_hash`].
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::weighted_hash;
+///
+/// assert!(weighted_hash(123456, &[1, 2, 3]) < 3)
+/// ```
+pub fn weighted_hash(key: u64, weights: &[u32]) -> usize {
+    node_of(key, weights.iter().copied())
+}
+
+/// Same as [`weighted_hash`], but returns `None` instead of panicking if the weights sum to zero,
+/// e.g. when every node is drained, or to more than `u32::MAX`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{try_weighted_hash, weighted_hash};
+///
+/// assert_eq!(try_weighted_hash(123456, &[0, 2, 3]), Some(weighted_hash(123456, &[0, 2, 3])));
+/// assert_eq!(try_weighted_hash(123456, &[0, 0]), None)
+/// ```
+pub fn try_weighted_hash(key: u64, weights: &[u32]) -> Option<usize> {
+    let total = weights.iter().map(|weight| *weight as u64).sum::<u64>();
+    let total = u32::try_from(total).ok().filter(|total| *total > 0)?;
+    Some(owner_of(hash(key, total), weights.iter().copied()))
+}
+
+/// Seed of the key mixing in [`variant`], decorrelating experiment variants from shard routing of the same key.
+const VARIANT_SEED: u64 = 0x5eed_ab7e_57ab_1e00;
+
+/// Assigns `key`, e.g. a user id, to an experiment variant, with `ratios` as the relative sizes of the variants.
+/// The key is mixed before [`weighted_hash`], so the variant is independent of the bucket [`hash`] routes
+/// the same key to, while a key always gets the same variant.
+///
+/// Panics if the ratios sum to zero or to more than `u32::MAX`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::variant;
+///
+/// assert_eq!(variant(123456, &[90, 10]), variant(123456, &[90, 10]))
+/// ```
+pub fn variant(key: u64, ratios: &[u32]) -> usize {
+    weighted_hash(mix64(key ^ VARIANT_SEED), ratios)
+}
+
+/// Same as [`weighted_hash`], but also returns the share of the total weight the chosen node holds,
+/// e.g. for dashboards showing that a key went to a node holding 12% of the capacity.
+///
+/// Panics if the weights sum to zero or to more than `u32::MAX`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::{weighted_hash, weighted_route_with_share};
+///
+/// let weights = [1, 1, 2];
+/// let (node, share) = weighted_route_with_share(123456, &weights);
+///
+/// assert_eq!(node, weighted_hash(123456, &weights));
+/// assert_eq!(share, weights[node] as f64 / 4.0)
+/// ```
+pub fn weighted_route_with_share(key: u64, weights: &[u32]) -> (usize, f64) {
+    let node = weighted_hash(key, weights);
+    let total: u64 = weights.iter().map(|weight| *weight as u64).sum();
+    (node, weights[node] as f64 / total as f64)
+}
+
+/// Same as [`weighted_hash`], but reads the weight of every node with `weight_of` and returns the node itself,
+/// so nodes carrying their own weight don't need a parallel weights slice.
+///
+/// Panics if the weights sum to zero or to more than `u32::MAX`.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::weighted_route;
+///
+/// struct Node {
+///     name: &'static str,
+///     weight: u32,
+/// }
+///
+/// let nodes = [Node { name: "a", weight: 1 }, Node { name: "b", weight: 0 }];
+///
+/// assert_eq!(weighted_route(123456, &nodes, |node| node.weight).name, "a")
+/// ```
+pub fn weighted_route<T, F: Fn(&T) -> u32>(key: u64, nodes: &[T], weight_of: F) -> &T {
+    &nodes[node_of(key, nodes.iter().map(&weight_of))]
+}
+
+/// The number of integer weight units the fractional weights are scaled to.
+const F64_RESOLUTION: f64 = (1u32 << 24) as f64;
+
+/// Same as [`weighted_hash`] for fractional weights, e.g. CPU shares like `1.5`.
+/// The weights are scaled to integers with a total of about `2^24`, so the shares are kept to about 7 digits.
+///
+/// Panics if any weight is negative, NaN or infinite, or if all weights are zero.
+///
+/// Usage example:
+/// ```rust
+/// use jumpch::weighted_hash_f64;
+///
+/// assert!(weighted_hash_f64(123456, &[1.5, 1.0]) < 2)
+/// ```
+pub fn weighted_hash_f64(key: u64, weights: &[f64]) -> usize {
+    assert!(
+        weights
+            .iter()
+            .all(|weight| weight.is_finite() && *weight >= 0.0),
+        "weights must be finite and non-negative"
+    );
+    let total: f64 = weights.iter().sum();
+    assert!(total > 0.0, "weights must sum to more than 0");
+
+    let scale = F64_RESOLUTION / total;
+    let scaled: Vec<u32> = weights
+        .iter()
+        .map(|weight| {
+            // Positive weights keep at least one unit, so they are never dropped by rounding.
+            if *weight > 0.0 {
+                ((weight * scale).round() as u32).max(1)
+            } else {
+                0
+            }
+        })
+        .collect();
+    weighted_hash(key, &scaled)
+}
+
+/// Jump hashes `key` over the total weight and returns the node whose contiguous weight range contains the bucket.
+fn node_of<I: Iterator<Item = u32> + Clone>(key: u64, weights: I) -> usize {
+    let total = weights.clone().map(|weight| weight as u64).sum::<u64>();
+    let total = u32::try_from(total).expect("weights must sum to at most u32::MAX");
+    assert!(total > 0, "weights must sum to more than 0");
+
+    owner_of(hash(key, total), weights)
+}
+
+/// Returns the node whose contiguous weight range contains `bucket`, which must be less than the total weight.
+fn owner_of<I: Iterator<Item = u32>>(bucket: u32, weights: I) -> usize {
+    let bucket = bucket as u64;
+    let mut end = 0u64;
+    weights
+        .into_iter()
+        .position(|weight| {
+            end += weight as u64;
+            bucket < end
+        })
+        .expect("bucket is less than the total weight")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        try_weighted_hash, variant, weighted_hash, weighted_hash_f64, weighted_route,
+        weighted_route_with_share,
+    };
+
+    #[test]
+    fn test_weighted_hash() {
+        let weights = [1, 2, 3, 4];
+        let mut counts = [0u32; 4];
+        for key in 0..100_000 {
+            counts[weighted_hash(key, &weights)] += 1;
+        }
+        for (count, weight) in counts.iter().zip(weights) {
+            let expected = 10_000 * weight;
+            assert!(count.abs_diff(expected) < expected / 20);
+        }
+    }
+
+    #[test]
+    fn test_weighted_hash_growth() {
+        for key in 0..10_000 {
+            let (old, new) = (weighted_hash(key, &[3, 3]), weighted_hash(key, &[3, 3, 2]));
+            assert!(old == new || new == 2);
+        }
+    }
+
+    #[test]
+    fn test_variant() {
+        let in_control = (0..100_000)
+            .filter(|user| variant(*user, &[90, 10]) == 0)
+            .count();
+        assert!(in_control.abs_diff(90_000) < 1000, "{in_control}");
+
+        for user in 0..1000 {
+            assert_eq!(variant(user, &[90, 10]), variant(user, &[90, 10]));
+            assert_eq!(variant(user, &[0, 1]), 1);
+        }
+    }
+
+    #[test]
+    fn test_weighted_route() {
+        #[derive(Debug, PartialEq)]
+        struct Node {
+            id: usize,
+            weight: u32,
+        }
+
+        let nodes: Vec<Node> = [1, 4, 0, 2]
+            .into_iter()
+            .enumerate()
+            .map(|(id, weight)| Node { id, weight })
+            .collect();
+        let mut counts = [0u32; 4];
+        for key in 0..10_000 {
+            let node = weighted_route(key, &nodes, |node| node.weight);
+            assert_eq!(node.id, weighted_hash(key, &[1, 4, 0, 2]));
+            counts[node.id] += 1;
+        }
+        assert_eq!(counts[2], 0);
+        assert!(counts[1] > counts[3] && counts[3] > counts[0]);
+    }
+
+    #[test]
+    fn test_weighted_route_with_share() {
+        let weights = [12, 0, 38, 50];
+        for key in 0..1000 {
+            let (node, share) = weighted_route_with_share(key, &weights);
+            assert_eq!(node, weighted_hash(key, &weights));
+            assert_eq!(share, weights[node] as f64 / 100.0);
+            assert!(share > 0.0);
+        }
+        assert_eq!(weighted_route_with_share(42, &[7]), (0, 1.0));
+    }
+
+    #[test]
+    fn test_weighted_hash_f64() {
+        let mut counts = [0u32; 2];
+        for key in 0..100_000 {
+            counts[weighted_hash_f64(key, &[1.5, 1.0])] += 1;
+        }
+        let ratio = counts[0] as f64 / counts[1] as f64;
+        assert!((ratio - 1.5).abs() < 0.05, "{ratio}");
+    }
+
+    #[test]
+    fn test_weighted_hash_f64_tiny_total() {
+        for key in 0..1000 {
+            assert!(weighted_hash_f64(key, &[0.001, 0.0005]) < 2);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "weights must be finite and non-negative")]
+    fn test_negative_weight() {
+        weighted_hash_f64(1, &[1.0, -1.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "weights must be finite and non-negative")]
+    fn test_nan_weight() {
+        weighted_hash_f64(1, &[1.0, f64::NAN]);
+    }
+
+    #[test]
+    fn test_try_weighted_hash() {
+        let weights = [0, 3, 0, 1, 0];
+        let mut counts = [0u32; 5];
+        for key in 0..10_000 {
+            let node = try_weighted_hash(key, &weights).unwrap();
+            assert_eq!(node, weighted_hash(key, &weights));
+            counts[node] += 1;
+        }
+        assert_eq!((counts[0], counts[2], counts[4]), (0, 0, 0));
+        assert!(counts[1] > 2 * counts[3]);
+
+        assert_eq!(try_weighted_hash(1, &[0, 0, 0]), None);
+        assert_eq!(try_weighted_hash(1, &[]), None);
+        assert_eq!(try_weighted_hash(1, &[u32::MAX, 1]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "weights must sum to more than 0")]
+    fn test_zero_weights() {
+        weighted_hash(1, &[0, 0]);
+    }
+}